@@ -0,0 +1,182 @@
+use crate::game::*;
+use crate::player::Player;
+
+pub const DEFAULT_DEPTH: usize = 5;
+pub const MAX_DEPTH: usize = 10;
+
+const WIN_SCORE: i32 = 1_000_000;
+
+/// Scores a position from the point of view of `team`. Higher is better.
+pub trait Evaluator {
+    fn evaluate(&self, game: &GameState, team: Team) -> i32;
+}
+
+impl<F> Evaluator for F
+where
+    F: Fn(&GameState, Team) -> i32,
+{
+    fn evaluate(&self, game: &GameState, team: Team) -> i32 {
+        self(game, team)
+    }
+}
+
+/// Rewards every window of `winning_length` cells that only one team has
+/// chips in, weighting windows that are closer to complete more heavily.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct WindowEvaluator;
+
+impl Evaluator for WindowEvaluator {
+    fn evaluate(&self, game: &GameState, team: Team) -> i32 {
+        game.windows()
+            .map(|window| {
+                let mut owner = None;
+                let mut count = 0;
+                for (row, column) in window {
+                    match (owner, game.cell(row, column)) {
+                        (_, None) => continue,
+                        (None, Some(chip)) => owner = Some(chip),
+                        (Some(o), Some(chip)) if o == chip => (),
+                        _ => return 0,
+                    }
+                    count += 1;
+                }
+                let value = count * count;
+                match owner {
+                    Some(o) if o == team => value,
+                    Some(_) => -value,
+                    None => 0,
+                }
+            })
+            .sum()
+    }
+}
+
+/// A computer player that searches `depth` moves ahead with negamax and
+/// alpha-beta pruning. With more than two teams the search is "paranoid":
+/// every other team is assumed to be working against the searching team.
+pub struct Ai<E = WindowEvaluator> {
+    depth: usize,
+    evaluator: E,
+}
+
+impl Ai<WindowEvaluator> {
+    pub fn new(depth: usize) -> Ai<WindowEvaluator> {
+        Ai::with_evaluator(depth, WindowEvaluator)
+    }
+}
+
+impl<E: Evaluator> Ai<E> {
+    pub fn with_evaluator(depth: usize, evaluator: E) -> Ai<E> {
+        Ai { depth, evaluator }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the column of the best move for the team whose turn it is,
+    /// or `None` if there are no legal moves.
+    pub fn choose_column(&self, game: &GameState) -> Option<usize> {
+        self.best_move(game).map(Move::column)
+    }
+
+    /// Returns the best move for the team whose turn it is, or `None` if
+    /// there are no legal moves.
+    pub fn best_move(&self, game: &GameState) -> Option<Move> {
+        let team = game.cur_turn();
+        let mut alpha = -WIN_SCORE * 2;
+        let beta = WIN_SCORE * 2;
+        let mut best = None;
+        for (mv, child) in Ai::<E>::children(game) {
+            let depth = self.depth.saturating_sub(1);
+            let score = self.score_child(game, &child, team, depth, alpha, beta);
+            if best.is_none() || score > alpha {
+                alpha = score;
+                best = Some(mv);
+            }
+        }
+        best
+    }
+
+    /// Scores `game` for the side whose turn it is, where the sides are
+    /// `team` and everyone else.
+    fn negamax(
+        &self,
+        game: &GameState,
+        team: Team,
+        depth: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        let sign = if game.cur_turn() == team { 1 } else { -1 };
+        match game.outcome() {
+            GameOutcome::Won(winner) if winner == team => return sign * (WIN_SCORE + depth as i32),
+            GameOutcome::Won(_) => return -sign * (WIN_SCORE + depth as i32),
+            GameOutcome::Draw => return 0,
+            GameOutcome::InProgress => (),
+        }
+        if depth == 0 {
+            return sign * self.evaluator.evaluate(game, team);
+        }
+        let mut best = -WIN_SCORE * 2;
+        for (_, child) in Ai::<E>::children(game) {
+            let score = self.score_child(game, &child, team, depth - 1, alpha, beta);
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Scores `child` for the side whose turn it is in `parent`.
+    fn score_child(
+        &self,
+        parent: &GameState,
+        child: &GameState,
+        team: Team,
+        depth: usize,
+        alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        let same_side = (child.cur_turn() == team) == (parent.cur_turn() == team);
+        if same_side {
+            self.negamax(child, team, depth, alpha, beta)
+        } else {
+            -self.negamax(child, team, depth, -beta, -alpha)
+        }
+    }
+
+    /// Every legal move from `game`, searching drops before pops and moves
+    /// near the center of the board first.
+    fn children(game: &GameState) -> impl Iterator<Item = (Move, GameState)> + '_ {
+        let center = game.num_columns() / 2;
+        let center_row = game.num_rows() / 2;
+        let mut moves = game.available_moves();
+        moves.sort_by_key(|&mv| {
+            let column = mv.column();
+            let is_pop = matches!(mv, Move::Pop(_));
+            let row_distance = match mv {
+                Move::Place(row, _) => center_row.abs_diff(row),
+                _ => 0,
+            };
+            let distance = center.abs_diff(column) + row_distance;
+            (is_pop, distance, column > center)
+        });
+        moves.into_iter().map(move |mv| {
+            let mut child = game.clone();
+            child
+                .play_move(game.cur_turn(), mv)
+                .expect("available moves are legal");
+            (mv, child)
+        })
+    }
+}
+
+impl<E: Evaluator> Player for Ai<E> {
+    fn choose_move(&mut self, game: &GameState) -> Move {
+        self.best_move(game)
+            .expect("asked for a move in a game that is over")
+    }
+}
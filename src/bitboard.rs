@@ -0,0 +1,237 @@
+use crate::game::*;
+use array2d::Array2D;
+
+pub const MAX_CELLS: usize = 64;
+pub const MAX_COLUMNS: usize = MAX_CELLS;
+
+const NUM_TEAMS: usize = 2;
+
+/// A compact two-team game that mirrors the public API of `GameState`.
+///
+/// Each team's chips are stored in a `u128` with one bit per cell, laid out
+/// column by column from the bottom up. Every column has an extra empty
+/// sentinel bit on top so that lines cannot wrap from one column to the next,
+/// which takes at most 128 bits for a board of up to `MAX_CELLS` cells.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BitBoard {
+    teams: [u128; NUM_TEAMS],
+    heights: [u8; MAX_COLUMNS],
+    cur_turn: Team,
+    num_rows: usize,
+    num_columns: usize,
+    winning_length: usize,
+    num_moves: usize,
+    winner: Option<Team>,
+}
+
+impl Default for BitBoard {
+    fn default() -> BitBoard {
+        BitBoard::new(
+            DEFAULT_FIRST_TURN,
+            DEFAULT_NUM_ROWS,
+            DEFAULT_NUM_COLUMNS,
+            DEFAULT_WINNING_LENGTH,
+        )
+        .unwrap()
+    }
+}
+
+impl BitBoard {
+    pub fn new(
+        first_turn: Team,
+        num_rows: usize,
+        num_columns: usize,
+        winning_length: usize,
+    ) -> Result<BitBoard, Error> {
+        if first_turn.index() >= NUM_TEAMS {
+            return Err(Error::InvalidTeam);
+        }
+        validate_board(num_rows, num_columns, winning_length)?;
+        if num_rows * num_columns > MAX_CELLS {
+            return Err(Error::UnsupportedBoard);
+        }
+        Ok(BitBoard {
+            teams: [0; NUM_TEAMS],
+            heights: [0; MAX_COLUMNS],
+            cur_turn: first_turn,
+            num_rows,
+            num_columns,
+            winning_length,
+            num_moves: 0,
+            winner: None,
+        })
+    }
+
+    /// Converts a two-team `GameState` on a flat board of up to `MAX_CELLS`,
+    /// where chips are only ever dropped and the first long enough line wins.
+    /// Fails with `Error::UnsupportedBoard` otherwise.
+    pub fn from_game_state(game: &GameState) -> Result<BitBoard, Error> {
+        if game.num_teams() != NUM_TEAMS
+            || game.pop_out()
+            || game.topology() != Topology::Flat
+            || game.placement() != Placement::Gravity
+            || game.win_rule() != WinRule::AtLeast
+            || game.scoring() != ScoringMode::FirstLine
+        {
+            return Err(Error::UnsupportedBoard);
+        }
+        let mut board = BitBoard::new(
+            game.cur_turn(),
+            game.num_rows(),
+            game.num_columns(),
+            game.winning_length(),
+        )?;
+        for column in 0..game.num_columns() {
+            for row in 0..game.num_rows() {
+                let team = match game.cell(row, column) {
+                    Some(team) => team,
+                    None => break,
+                };
+                board.teams[team.index()] |= board.bit(row, column);
+                board.heights[column] += 1;
+                board.num_moves += 1;
+            }
+        }
+        board.winner = (0..NUM_TEAMS)
+            .map(Team::new)
+            .find(|&team| board.has_line(board.teams[team.index()]));
+        Ok(board)
+    }
+
+    /// Converts back into a `GameState`. The move order is not stored in a
+    /// `BitBoard`, so the returned game has no history to undo.
+    pub fn to_game_state(&self) -> GameState {
+        let mut cells = Array2D::filled_with(None, self.num_rows, self.num_columns);
+        for column in 0..self.num_columns {
+            for row in 0..self.num_rows {
+                cells[(row, column)] = self.cell(row, column);
+            }
+        }
+        GameState::from_cells(cells, self.cur_turn, NUM_TEAMS, self.winning_length)
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.num_columns
+    }
+
+    pub fn num_teams(&self) -> usize {
+        NUM_TEAMS
+    }
+
+    pub fn winning_length(&self) -> usize {
+        self.winning_length
+    }
+
+    pub fn cur_turn(&self) -> Team {
+        self.cur_turn
+    }
+
+    pub fn drop_chip(&mut self, team: Team, column: usize) -> Result<(), Error> {
+        if self.game_over() {
+            return Err(Error::GameOver);
+        }
+        if self.cur_turn != team {
+            return Err(Error::NotThatTeamsTurn);
+        }
+        if column >= self.num_columns {
+            return Err(Error::OutOfBounds);
+        }
+        if !self.can_play(column) {
+            return Err(Error::ColumnFull);
+        }
+        self.play(column);
+        Ok(())
+    }
+
+    /// Whether a chip can be dropped in `column`, ignoring whose turn it is
+    /// and whether the game is over.
+    pub fn can_play(&self, column: usize) -> bool {
+        (self.heights[column] as usize) < self.num_rows
+    }
+
+    /// Drops a chip for the current team without any validation. The caller
+    /// must make sure the game is not over and that `can_play(column)` holds.
+    pub fn play(&mut self, column: usize) {
+        let team = self.cur_turn.index();
+        let row = self.heights[column] as usize;
+        self.teams[team] |= self.bit(row, column);
+        self.heights[column] += 1;
+        self.num_moves += 1;
+        if self.has_line(self.teams[team]) {
+            self.winner = Some(self.cur_turn);
+        }
+        self.cur_turn = Team::new(1 - team);
+    }
+
+    pub fn game_over(&self) -> bool {
+        self.outcome() != GameOutcome::InProgress
+    }
+
+    pub fn outcome(&self) -> GameOutcome {
+        match self.winner {
+            Some(team) => GameOutcome::Won(team),
+            None if self.num_moves == self.num_rows * self.num_columns => GameOutcome::Draw,
+            None => GameOutcome::InProgress,
+        }
+    }
+
+    pub fn has_won(&self, team: Team) -> bool {
+        self.winner == Some(team)
+    }
+
+    pub fn who_won(&self) -> Option<Team> {
+        self.winner
+    }
+
+    pub fn to_string_arr(&self) -> Vec<String> {
+        self.to_game_state().to_string_arr()
+    }
+
+    /// The number of chips that have been dropped so far.
+    pub fn num_moves(&self) -> usize {
+        self.num_moves
+    }
+
+    /// A value that uniquely identifies the position for a given board size.
+    pub fn key(&self) -> u128 {
+        let (current, occupied) = self.current_and_occupied();
+        let bottom = (0..self.num_columns).fold(0, |acc, column| acc | self.bit(0, column));
+        current + occupied + bottom
+    }
+
+    /// The chips of the team whose turn it is, and all chips on the board.
+    pub(crate) fn current_and_occupied(&self) -> (u128, u128) {
+        let occupied = self.teams[0] | self.teams[1];
+        (self.teams[self.cur_turn.index()], occupied)
+    }
+
+    fn cell(&self, row: usize, column: usize) -> Option<Team> {
+        let bit = self.bit(row, column);
+        (0..NUM_TEAMS)
+            .map(Team::new)
+            .find(|team| self.teams[team.index()] & bit != 0)
+    }
+
+    fn bit(&self, row: usize, column: usize) -> u128 {
+        1 << (column * (self.num_rows + 1) + row)
+    }
+
+    fn has_line(&self, chips: u128) -> bool {
+        let height = self.num_rows + 1;
+        [1, height, height - 1, height + 1]
+            .iter()
+            .any(|&shift| self.has_line_in_direction(chips, shift))
+    }
+
+    fn has_line_in_direction(&self, chips: u128, shift: usize) -> bool {
+        let mut remaining = chips;
+        for _ in 1..self.winning_length {
+            remaining &= remaining.checked_shr(shift as u32).unwrap_or(0);
+        }
+        remaining != 0
+    }
+}
@@ -1,5 +1,141 @@
+use crate::ai::{self, Ai};
 use crate::game::*;
-use std::io;
+use crate::mcts::{Budget, Mcts};
+use crate::player::{Match, Player};
+use crate::render::{Palette, Renderer};
+use crate::save;
+use crate::solver::Solver;
+use std::cmp::Reverse;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fmt, io, process};
+
+const MAX_PLAYOUTS: usize = 1_000_000;
+
+/// The options that take a value, either after `=` or as the next argument.
+const VALUE_FLAGS: [&str; 13] = [
+    "--rows",
+    "--columns",
+    "--teams",
+    "--win-length",
+    "--win-rule",
+    "--first",
+    "--ai",
+    "--mcts",
+    "--name",
+    "--glyph",
+    "--color",
+    "--seed",
+    "--load",
+];
+
+/// The options that set up a new game, which a saved game already decides.
+const GAME_FLAGS: [&str; 10] = [
+    "--rows",
+    "--columns",
+    "--teams",
+    "--win-length",
+    "--win-rule",
+    "--first",
+    "--pop-out",
+    "--cylinder",
+    "--free-placement",
+    "--count-lines",
+];
+
+const USAGE: &str = "Usage: connect-four [OPTIONS]
+
+Without options the game asks how to set up the board.
+
+Options:
+    --rows <n>             number of rows on the board
+    --columns <n>          number of columns on the board
+    --teams <n>            number of teams
+    --win-length <n>       number of consecutive chips needed to win
+    --win-rule <rule>      at-least (the default), exactly (longer lines do not
+                           count) or overline-loses (longer lines lose, two teams)
+    --pop-out              let teams pop their own chips out of the bottom row
+    --cylinder             join the left and right edges of the board
+    --free-placement       place chips on any empty cell instead of dropping them
+    --count-lines          fill the whole board and rank teams by how many lines
+                           they made
+    --first <team>         team that goes first
+    --ai <team>:<level>    let the computer play a team, looking <level> moves ahead
+    --mcts <team>:<n>      let the computer play a team using <n> random playouts per move
+    --seed <n>             seed for the random playouts, to repeat a game
+    --name <team>:<name>   name a team
+    --glyph <team>:<text>  draw a team's chips with a symbol on a plain board
+    --color <team>:<n>     draw a team's chips in a 256-color terminal color
+    --load <file>          resume a game saved with the save command
+    --plain                draw the board without colors
+    --high-contrast        draw the board with a high-contrast palette
+    --help                 print this message";
+
+enum TurnInput {
+    Column(usize),
+    Pop(usize),
+    Place(usize, usize),
+    Undo,
+    Redo,
+    Save(PathBuf),
+    Load(PathBuf),
+    Analyze,
+}
+
+/// How a team played by the computer chooses its moves.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ComputerSetup {
+    Search(usize),
+    Mcts(usize),
+}
+
+impl ComputerSetup {
+    fn into_player(self, seed: u64) -> Box<dyn Player> {
+        match self {
+            ComputerSetup::Search(depth) => Box::new(Ai::new(depth)),
+            ComputerSetup::Mcts(playouts) => {
+                Box::new(Mcts::new(Budget::Iterations(playouts), seed))
+            }
+        }
+    }
+}
+
+/// A change to how a team is shown, given on the command line.
+enum TeamInfoChange {
+    Name(String),
+    Glyph(String),
+    Color(u8),
+}
+
+type Computers = Vec<(Team, Box<dyn Player>)>;
+
+/// A person typing column numbers, or rows and columns in a free placement
+/// game, for use with `Match::play`. The interactive game reads human moves
+/// itself so that it can also offer commands like undo.
+pub struct StdinPlayer;
+
+impl Player for StdinPlayer {
+    fn choose_move(&mut self, game: &GameState) -> Move {
+        println!("{}'s turn:", game.team_name(game.cur_turn()));
+        while game.placement() == Placement::Free {
+            let row = get_usize_from_user_in_range("the row to place tile on", 0, game.num_rows());
+            let column =
+                get_usize_from_user_in_range("the column to place tile on", 0, game.num_columns());
+            if game.legal_places().contains(&(row, column)) {
+                return Move::Place(row, column);
+            }
+            print_error(Error::CellOccupied);
+        }
+        loop {
+            let column =
+                get_usize_from_user_in_range("the column to drop tile in", 0, game.num_columns());
+            if game.legal_moves().contains(&column) {
+                return Move::Drop(column);
+            }
+            print_error(Error::ColumnFull);
+        }
+    }
+}
 
 struct Args {
     num_teams: usize,
@@ -7,99 +143,582 @@ struct Args {
     num_rows: usize,
     num_columns: usize,
     winning_length: usize,
+    pop_out: bool,
+    topology: Topology,
+    placement: Placement,
+    win_rule: WinRule,
+    scoring: ScoringMode,
+    computers: Vec<(Team, ComputerSetup)>,
+    team_info: Vec<(Team, TeamInfoChange)>,
+    seed: Option<u64>,
+    load: Option<PathBuf>,
+    plain: bool,
+    palette: Palette,
+}
+
+impl Default for Args {
+    fn default() -> Args {
+        Args {
+            num_teams: DEFAULT_NUM_TEAMS,
+            first_turn: DEFAULT_FIRST_TURN,
+            num_rows: DEFAULT_NUM_ROWS,
+            num_columns: DEFAULT_NUM_COLUMNS,
+            winning_length: DEFAULT_WINNING_LENGTH,
+            pop_out: false,
+            topology: Topology::Flat,
+            placement: Placement::Gravity,
+            win_rule: WinRule::AtLeast,
+            scoring: ScoringMode::FirstLine,
+            computers: Vec::new(),
+            team_info: Vec::new(),
+            seed: None,
+            load: None,
+            plain: false,
+            palette: Palette::Standard,
+        }
+    }
+}
+
+impl Args {
+    fn config(&self) -> GameConfig {
+        GameConfig {
+            first_turn: self.first_turn,
+            num_teams: self.num_teams,
+            num_rows: self.num_rows,
+            num_columns: self.num_columns,
+            winning_length: self.winning_length,
+            pop_out: self.pop_out,
+            topology: self.topology,
+            placement: self.placement,
+            win_rule: self.win_rule,
+            scoring: self.scoring,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum ArgsError {
+    Help,
+    UnknownFlag(String),
+    MissingValue(String),
+    InvalidValue(String, String),
+    /// A game setting given along with `--load`.
+    ConflictsWithLoad(String),
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgsError::Help => write!(f, "{}", USAGE),
+            ArgsError::UnknownFlag(flag) => write!(f, "unknown option {}", flag),
+            ArgsError::MissingValue(flag) => write!(f, "{} needs a value", flag),
+            ArgsError::InvalidValue(flag, value) => {
+                write!(f, "{:?} is not a valid value for {}", value, flag)
+            }
+            ArgsError::ConflictsWithLoad(flag) => {
+                write!(f, "{} cannot be used with --load", flag)
+            }
+        }
+    }
+}
+
+/// A match ready to play, as set up by flags or prompts.
+pub(crate) struct Setup {
+    pub(crate) game_match: Match,
+    pub(crate) computer_teams: Vec<Team>,
+    pub(crate) renderer: Renderer,
 }
 
 pub fn run() {
-    println!("Running CLI");
-    let mut game = get_game_from_user();
-    while !game.game_over() {
-        play_turn(&mut game);
+    let Setup {
+        mut game_match,
+        computer_teams,
+        renderer,
+    } = match setup() {
+        Some(setup) => setup,
+        None => return,
+    };
+    while !game_match.game().game_over() {
+        if computer_teams.contains(&game_match.game().cur_turn()) {
+            play_computer_turn(&mut game_match, renderer);
+        } else {
+            play_turn(&mut game_match, &computer_teams, renderer);
+        }
     }
-    display_end(&game);
+    display_end(game_match.game(), renderer);
+}
+
+/// Sets up a match from the command line flags, or by asking if there were
+/// none. Returns `None` if only the usage was asked for.
+pub(crate) fn setup() -> Option<Setup> {
+    let (game, computers, renderer) = match parse_args(env::args().skip(1)) {
+        Ok(Some(args)) => {
+            let renderer = if args.plain {
+                Renderer::Plain
+            } else {
+                Renderer::for_terminal(args.palette)
+            };
+            let (game, computers) = get_game_from_args(args);
+            (game, computers, renderer)
+        }
+        Ok(None) => {
+            println!("Running CLI");
+            let mut game = get_game_from_user();
+            get_team_info_from_user(&mut game);
+            let computers = get_computers_from_user(&game);
+            (game, computers, Renderer::for_terminal(Palette::Standard))
+        }
+        Err(ArgsError::Help) => {
+            println!("{}", USAGE);
+            return None;
+        }
+        Err(e) => exit_with_error(e),
+    };
+    let computer_teams = computers.iter().map(|(team, _)| *team).collect();
+    Some(Setup {
+        game_match: create_match(game, computers),
+        computer_teams,
+        renderer,
+    })
 }
 
-fn play_turn(game: &mut GameState) {
-    display_board(&game);
+/// Seats the computers and a `StdinPlayer` for every other team.
+fn create_match(game: GameState, mut computers: Computers) -> Match {
+    let players = (0..game.num_teams())
+        .map(Team::new)
+        .map(|team| {
+            match computers
+                .iter()
+                .position(|(computer_team, _)| *computer_team == team)
+            {
+                Some(index) => computers.swap_remove(index).1,
+                None => Box::new(StdinPlayer),
+            }
+        })
+        .collect();
+    Match::new(game, players).expect("there is a player for every team")
+}
+
+fn play_turn(game_match: &mut Match, computer_teams: &[Team], renderer: Renderer) {
+    let game = game_match.game();
+    display_board(game, renderer);
     println!();
-    let team = game.cur_turn();
-    println!("{}'s turn:", team);
+    println!("{}'s turn:", game.team_name(game.cur_turn()));
     loop {
-        let col = get_usize_from_user_in_range("the column to drop tile in", 0, game.num_columns());
-        match game.drop_chip(team, col) {
+        let game = game_match.game();
+        let result = match get_turn_input_from_user(game) {
+            TurnInput::Column(col) => game_match.drop_chip(col),
+            TurnInput::Pop(col) => game_match.play_move(Move::Pop(col)),
+            TurnInput::Place(row, col) => game_match.play_move(Move::Place(row, col)),
+            TurnInput::Undo => undo_to_human_turn(game_match, computer_teams),
+            TurnInput::Redo => game_match.redo(),
+            TurnInput::Save(path) => {
+                match save::save(game, &path) {
+                    Ok(_) => println!("Saved the game to {}.", path.display()),
+                    Err(e) => println!("Could not save the game because {}.", e),
+                }
+                continue;
+            }
+            TurnInput::Analyze => {
+                display_analysis(game);
+                continue;
+            }
+            TurnInput::Load(path) => match save::load(&path) {
+                Ok(loaded) => game_match.set_game(loaded),
+                Err(e) => {
+                    println!("Could not load the game because {}.", e);
+                    continue;
+                }
+            },
+        };
+        match result {
             Ok(_) => break,
             Err(e) => print_error(e),
         }
     }
 }
 
-fn display_end(game: &GameState) {
-    clear_screen();
-    display_board(&game);
+fn play_computer_turn(game_match: &mut Match, renderer: Renderer) {
+    display_board(game_match.game(), renderer);
     println!();
-    println!("{} wins!", game.who_won().unwrap());
+    let game = game_match.game();
+    println!(
+        "{} (computer) is thinking...",
+        game.team_name(game.cur_turn())
+    );
+    let mv = game_match
+        .play_turn()
+        .expect("computer chose an illegal move");
+    if let Move::Pop(column) = mv {
+        println!("The computer popped a chip out of column {:X}.", column);
+    }
 }
 
-fn display_board(game: &GameState) {
+/// Undoes the last move, then keeps undoing computer moves so that the
+/// computer does not immediately replay them.
+pub(crate) fn undo_to_human_turn(
+    game_match: &mut Match,
+    computer_teams: &[Team],
+) -> Result<(), Error> {
+    game_match.undo()?;
+    while computer_teams.contains(&game_match.game().cur_turn()) && game_match.undo().is_ok() {}
+    Ok(())
+}
+
+fn display_analysis(game: &GameState) {
+    println!("Analyzing, this can take a while early in the game...");
+    match Solver::new().analyze(game) {
+        Ok(scores) => {
+            for (column, score) in scores.into_iter().enumerate() {
+                match score {
+                    Some(score) => println!("Column {:X}: {}", column, score),
+                    None => println!("Column {:X}: full", column),
+                }
+            }
+        }
+        Err(e) => println!("Could not analyze the game because {}.", e),
+    }
+}
+
+fn display_end(game: &GameState, renderer: Renderer) {
     clear_screen();
-    println!("{}", grid_string(&game));
+    let winning_cells: Vec<_> = game
+        .winning_lines()
+        .iter()
+        .flat_map(|line| line.cells.iter().cloned())
+        .collect();
+    display_highlighted_board(game, &winning_cells, renderer);
+    println!();
+    let ranking = ranking_lines(game);
+    if !ranking.is_empty() {
+        println!("{}\n", ranking.join("\n"));
+    }
+    println!("{}", outcome_message(game));
 }
 
-fn grid_string(game: &GameState) -> String {
-    let grid_s = game
-        .to_string_arr()
-        .into_iter()
-        .rev()
-        .map(|row| {
-            row.chars()
-                .map(|c| char::to_string(&c))
+/// Says who won the finished `game` and how, or why it was a draw.
+pub(crate) fn outcome_message(game: &GameState) -> String {
+    let counting = game.scoring() == ScoringMode::CountLines;
+    match game.outcome() {
+        GameOutcome::Won(team) if counting => {
+            format!("{} wins with the most lines!", game.team_name(team))
+        }
+        GameOutcome::Won(team) if game.winning_lines()[0].team != team => format!(
+            "{} wins because {} made a line longer than {}!",
+            game.team_name(team),
+            game.team_name(game.winning_lines()[0].team),
+            game.winning_length()
+        ),
+        GameOutcome::Won(team) => {
+            let directions = game
+                .winning_lines()
+                .iter()
+                .map(|line| line.direction.to_string())
                 .collect::<Vec<_>>()
-                .join(" ")
+                .join(" and ");
+            format!("{} wins! ({} line)", game.team_name(team), directions)
+        }
+        GameOutcome::Draw if counting => "The most lines are tied, it's a draw!".to_string(),
+        GameOutcome::Draw if game.is_full() => "The board is full, it's a draw!".to_string(),
+        GameOutcome::Draw => "The same position came up three times, it's a draw!".to_string(),
+        GameOutcome::InProgress => panic!("Game is not over yet"),
+    }
+}
+
+/// How many lines each team made, most first, in a game scored by counting
+/// lines. Tied teams share a place. Empty for other games.
+pub(crate) fn ranking_lines(game: &GameState) -> Vec<String> {
+    if game.scoring() != ScoringMode::CountLines {
+        return Vec::new();
+    }
+    let mut scores = game.scores();
+    scores.sort_by_key(|&(_, score)| Reverse(score));
+    scores
+        .iter()
+        .map(|&(team, score)| {
+            let place = scores.iter().position(|&(_, s)| s == score).unwrap() + 1;
+            let lines = if score == 1 { "line" } else { "lines" };
+            format!("{}. {}: {} {}", place, game.team_name(team), score, lines)
         })
-        .collect::<Vec<_>>()
-        .join("\n");
-    let header: String = (0..game.num_columns())
-        .map(|i| format!("{:X}", i))
-        .collect::<Vec<_>>()
-        .join(" ");
-    let lines = "-".repeat(game.num_columns() * 2 - 1);
-    format!("{}\n{}\n{}", header, lines, grid_s)
+        .collect()
+}
+
+fn display_board(game: &GameState, renderer: Renderer) {
+    display_highlighted_board(game, &[], renderer);
+}
+
+fn display_highlighted_board(game: &GameState, highlighted: &[(usize, usize)], renderer: Renderer) {
+    clear_screen();
+    println!("{}", renderer.render(game, highlighted));
 }
 
 fn print_error(err: Error) {
-    let message = match err {
-        Error::OutOfBounds => "that column was out of bounds",
-        Error::ColumnFull => "that column was full",
-        Error::NotThatTeamsTurn => "it was not that team's turn",
-        Error::InvalidTeam => "that was not a valid team",
-        Error::GameOver => "the game was already over",
-    };
-    println!("That was an invalid move because {}, try again.", message);
+    println!("That was an invalid move because {}, try again.", err);
 }
 
 fn clear_screen() {
     print!("{}[2J", 27 as char);
 }
 
+/// Parses command line flags, returning `None` if there were none so the
+/// setup should be asked for interactively.
+fn parse_args<I>(flags: I) -> Result<Option<Args>, ArgsError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut flags = flags.peekable();
+    if flags.peek().is_none() {
+        return Ok(None);
+    }
+    let mut args = Args::default();
+    let mut game_flag = None;
+    while let Some(flag) = flags.next() {
+        let (flag, value) = match flag.find('=') {
+            Some(index) => (
+                flag[..index].to_string(),
+                Some(flag[index + 1..].to_string()),
+            ),
+            None => (flag, None),
+        };
+        if game_flag.is_none() && GAME_FLAGS.contains(&flag.as_str()) {
+            game_flag = Some(flag.clone());
+        }
+        match flag.as_ref() {
+            "--help" | "-h" => return Err(ArgsError::Help),
+            "--plain" => {
+                args.plain = true;
+                continue;
+            }
+            "--high-contrast" => {
+                args.palette = Palette::HighContrast;
+                continue;
+            }
+            "--pop-out" => {
+                args.pop_out = true;
+                continue;
+            }
+            "--cylinder" => {
+                args.topology = Topology::Cylinder;
+                continue;
+            }
+            "--free-placement" => {
+                args.placement = Placement::Free;
+                continue;
+            }
+            "--count-lines" => {
+                args.scoring = ScoringMode::CountLines;
+                continue;
+            }
+            _ => {}
+        }
+        if !VALUE_FLAGS.contains(&flag.as_str()) {
+            return Err(ArgsError::UnknownFlag(flag));
+        }
+        let value = match value.or_else(|| flags.next()) {
+            Some(value) => value,
+            None => return Err(ArgsError::MissingValue(flag)),
+        };
+        match flag.as_ref() {
+            "--rows" => args.num_rows = parse_flag_value(&flag, &value)?,
+            "--columns" => args.num_columns = parse_flag_value(&flag, &value)?,
+            "--teams" => args.num_teams = parse_flag_value(&flag, &value)?,
+            "--win-length" => args.winning_length = parse_flag_value(&flag, &value)?,
+            "--win-rule" => {
+                args.win_rule = match value.as_ref() {
+                    "at-least" => WinRule::AtLeast,
+                    "exactly" => WinRule::Exactly,
+                    "overline-loses" => WinRule::OverlineLoses,
+                    _ => return Err(ArgsError::InvalidValue(flag, value)),
+                }
+            }
+            "--first" => args.first_turn = Team::new(parse_flag_value(&flag, &value)?),
+            "--ai" => {
+                let (team, depth) = parse_team_flag_value(&flag, &value, ai::MAX_DEPTH)?;
+                args.computers.push((team, ComputerSetup::Search(depth)));
+            }
+            "--mcts" => {
+                let (team, playouts) = parse_team_flag_value(&flag, &value, MAX_PLAYOUTS)?;
+                args.computers.push((team, ComputerSetup::Mcts(playouts)));
+            }
+            "--name" => {
+                let (team, name) = parse_team_and_value(&flag, &value)?;
+                args.team_info
+                    .push((team, TeamInfoChange::Name(name.to_string())));
+            }
+            "--glyph" => {
+                let (team, glyph) = parse_team_and_value(&flag, &value)?;
+                args.team_info
+                    .push((team, TeamInfoChange::Glyph(glyph.to_string())));
+            }
+            "--color" => {
+                let (team, color) = parse_team_and_value(&flag, &value)?;
+                let color = color
+                    .parse()
+                    .map_err(|_| ArgsError::InvalidValue(flag.clone(), value.clone()))?;
+                args.team_info.push((team, TeamInfoChange::Color(color)));
+            }
+            "--seed" => {
+                let seed = value
+                    .parse()
+                    .map_err(|_| ArgsError::InvalidValue(flag.clone(), value.clone()))?;
+                args.seed = Some(seed);
+            }
+            "--load" => args.load = Some(PathBuf::from(value)),
+            _ => return Err(ArgsError::UnknownFlag(flag)),
+        }
+    }
+    if let (Some(_), Some(flag)) = (&args.load, game_flag) {
+        return Err(ArgsError::ConflictsWithLoad(flag));
+    }
+    Ok(Some(args))
+}
+
+fn parse_flag_value(flag: &str, value: &str) -> Result<usize, ArgsError> {
+    value
+        .parse()
+        .map_err(|_| ArgsError::InvalidValue(flag.to_string(), value.to_string()))
+}
+
+/// Parses `<team>:<n>`, where `n` is how strong the computer is and must be
+/// between 1 and `max`.
+fn parse_team_flag_value(flag: &str, value: &str, max: usize) -> Result<(Team, usize), ArgsError> {
+    let invalid = || ArgsError::InvalidValue(flag.to_string(), value.to_string());
+    let (team, level) = parse_team_and_value(flag, value)?;
+    let level = parse_flag_value(flag, level).map_err(|_| invalid())?;
+    if level == 0 || level > max {
+        return Err(invalid());
+    }
+    Ok((team, level))
+}
+
+/// Splits `<team>:<rest>` at the first colon.
+fn parse_team_and_value<'a>(flag: &str, value: &'a str) -> Result<(Team, &'a str), ArgsError> {
+    let invalid = || ArgsError::InvalidValue(flag.to_string(), value.to_string());
+    let mut parts = value.splitn(2, ':');
+    let team = parts.next().ok_or_else(invalid)?;
+    let rest = parts.next().ok_or_else(invalid)?;
+    let team = parse_flag_value(flag, team).map_err(|_| invalid())?;
+    Ok((Team::new(team), rest))
+}
+
+fn get_game_from_args(args: Args) -> (GameState, Computers) {
+    let mut game = match args.load {
+        Some(path) => save::load(&path).unwrap_or_else(|e| {
+            exit_with_error(format!("could not load {} because {}", path.display(), e))
+        }),
+        None => GameState::from_config(&args.config())
+            .unwrap_or_else(|e| exit_with_error(format!("invalid game parameters because {}", e))),
+    };
+    for (team, change) in args.team_info {
+        if team.index() >= game.num_teams() {
+            exit_with_error(format!("{} is not playing, so it cannot be changed", team));
+        }
+        let mut info = game.team_info(team).clone();
+        match change {
+            TeamInfoChange::Name(name) => info.name = name,
+            TeamInfoChange::Glyph(glyph) => info.glyph = glyph,
+            TeamInfoChange::Color(color) => info.color = Some(color),
+        }
+        game.set_team_info(team, info)
+            .unwrap_or_else(|e| exit_with_error(format!("invalid team because {}", e)));
+    }
+    let seed = args.seed.unwrap_or_else(random_seed);
+    let mut computers = Vec::new();
+    for (team, setup) in args.computers {
+        if team.index() >= game.num_teams() {
+            exit_with_error(format!(
+                "{} is not playing, so the computer cannot play it",
+                team
+            ));
+        }
+        computers.push((
+            team,
+            setup.into_player(seed.wrapping_add(team.index() as u64)),
+        ));
+    }
+    (game, computers)
+}
+
+fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+fn exit_with_error<E: fmt::Display>(error: E) -> ! {
+    eprintln!("connect-four: {}", error);
+    eprintln!("Try --help for more information.");
+    process::exit(2);
+}
+
 fn get_game_from_user() -> GameState {
     clear_screen();
     loop {
         let args = get_args_from_user();
         let game_opt = match args {
-            Some(a) => GameState::new(
-                a.first_turn,
-                a.num_teams,
-                a.num_rows,
-                a.num_columns,
-                a.winning_length,
-            ),
+            Some(a) => GameState::from_config(&a.config()),
             None => Ok(GameState::default()),
         };
-        if let Ok(game_ok) = game_opt {
-            return game_ok;
-        } else {
-            println!("Invalid game parameters, try again.");
+        match game_opt {
+            Ok(game_ok) => return game_ok,
+            Err(e) => println!("Invalid game parameters because {}, try again.", e),
+        }
+    }
+}
+
+fn get_team_info_from_user(game: &mut GameState) {
+    println!("Would you like to name the teams?");
+    if !get_yes_no_from_user("whether to name the teams") {
+        return;
+    }
+    for team in (0..game.num_teams()).map(Team::new) {
+        loop {
+            let mut info = game.team_info(team).clone();
+            info.name = get_line_from_user(&format!("the name of {}", info.name));
+            info.glyph = get_line_from_user(&format!("the symbol for {}'s chips", info.name));
+            if let Some(color) = get_optional_color_from_user(&format!(
+                "the terminal color of {}'s chips",
+                info.name
+            )) {
+                info.color = Some(color);
+            }
+            match game.set_team_info(team, info) {
+                Ok(()) => break,
+                Err(e) => println!("Invalid team because {}, try again.", e),
+            }
+        }
+    }
+}
+
+fn get_computers_from_user(game: &GameState) -> Computers {
+    println!("Should the computer play any of the teams?");
+    if !get_yes_no_from_user("whether the computer should play") {
+        return Vec::new();
+    }
+    let mut computers = Vec::new();
+    for team in (0..game.num_teams()).map(Team::new) {
+        let name = game.team_name(team);
+        println!("Should the computer play {}?", name);
+        if get_yes_no_from_user(&format!("whether the computer should play {}", name)) {
+            println!("Should the computer use random playouts? They work better than looking ahead with more than two teams or large boards.");
+            let setup = if get_yes_no_from_user("whether to use random playouts") {
+                ComputerSetup::Mcts(get_usize_from_user_in_range(
+                    "the number of random playouts per move",
+                    1,
+                    MAX_PLAYOUTS + 1,
+                ))
+            } else {
+                ComputerSetup::Search(get_usize_from_user_in_range(
+                    "how many moves ahead the computer looks",
+                    1,
+                    ai::MAX_DEPTH + 1,
+                ))
+            };
+            computers.push((team, setup.into_player(random_seed())));
         }
     }
+    computers
 }
 
 fn get_args_from_user() -> Option<Args> {
@@ -108,20 +727,129 @@ fn get_args_from_user() -> Option<Args> {
     if yes {
         return None;
     }
-    let num_teams = get_usize_from_user_in_range("the number of teams", 0, 16);
+    let num_teams = get_usize_from_user("the number of teams");
+    if num_teams == 0 {
+        // There is no team to go first, so let `from_config` explain why
+        // before asking anything else.
+        return Some(Args {
+            num_teams,
+            ..Args::default()
+        });
+    }
     let num_rows = get_usize_from_user("the number of rows");
     let num_columns = get_usize_from_user("the number of columns");
     let winning_length = get_usize_from_user("the number of consecutive tiles you need to win");
     let first_turn = get_usize_from_user_in_range("the team to go first", 0, num_teams);
+    println!("Play PopOut, where teams may pop their own chips out of the bottom row?");
+    let pop_out = get_yes_no_from_user("whether to play PopOut");
+    println!("Join the left and right edges so lines can wrap around the board?");
+    let topology = if get_yes_no_from_user("whether lines wrap around") {
+        Topology::Cylinder
+    } else {
+        Topology::Flat
+    };
+    println!("Place chips on any empty cell instead of dropping them?");
+    let placement = if get_yes_no_from_user("whether to place chips anywhere") {
+        Placement::Free
+    } else {
+        Placement::Gravity
+    };
+    println!("Should lines longer than the winning length not count?");
+    let win_rule = if !get_yes_no_from_user("whether longer lines do not count") {
+        WinRule::AtLeast
+    } else {
+        println!("Should a team that makes a longer line lose?");
+        if get_yes_no_from_user("whether longer lines lose") {
+            WinRule::OverlineLoses
+        } else {
+            WinRule::Exactly
+        }
+    };
+    let scoring = if pop_out || win_rule != WinRule::AtLeast {
+        ScoringMode::FirstLine
+    } else {
+        println!(
+            "Keep playing until the board is full and rank teams by how many lines they made?"
+        );
+        if get_yes_no_from_user("whether to count lines") {
+            ScoringMode::CountLines
+        } else {
+            ScoringMode::FirstLine
+        }
+    };
     Some(Args {
         num_teams,
         num_rows,
         num_columns,
         winning_length,
+        pop_out,
+        topology,
+        placement,
+        win_rule,
+        scoring,
         first_turn: Team::new(first_turn),
+        ..Args::default()
     })
 }
 
+fn get_turn_input_from_user(game: &GameState) -> TurnInput {
+    let num_rows = game.num_rows();
+    let num_columns = game.num_columns();
+    let free = game.placement() == Placement::Free;
+    let pop_out = game.pop_out();
+    let pop = if pop_out { ", \"pop <column>\"" } else { "" };
+    if free {
+        println!(
+            "Please enter a row between 0 and {} and a column between 0 and {} to place tile on, such as \"0 {}\", or \"undo\", \"redo\", \"save <file>\", \"load <file>\" or \"analyze\".",
+            num_rows - 1,
+            num_columns - 1,
+            num_columns / 2
+        );
+    } else {
+        println!(
+            "Please enter a number for the column to drop tile in between 0 and {}{}, \"undo\", \"redo\", \"save <file>\", \"load <file>\" or \"analyze\".",
+            num_columns - 1,
+            pop
+        );
+    }
+    let mut input = String::new();
+    loop {
+        input.clear();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("failed to read stdin");
+        let mut words = input.trim().splitn(2, char::is_whitespace);
+        let command = words.next().unwrap_or("").to_lowercase();
+        let argument = words.next().map(str::trim);
+        match (command.as_ref(), argument) {
+            ("undo", None) | ("u", None) => return TurnInput::Undo,
+            ("redo", None) | ("r", None) => return TurnInput::Redo,
+            ("analyze", None) | ("a", None) => return TurnInput::Analyze,
+            ("save", Some(path)) => return TurnInput::Save(PathBuf::from(path)),
+            ("load", Some(path)) => return TurnInput::Load(PathBuf::from(path)),
+            ("pop", Some(column)) | ("p", Some(column)) if pop_out => match column.parse() {
+                Ok(n) if n < num_columns => return TurnInput::Pop(n),
+                Ok(_) => println!("Not between 0 and {}, try again.", num_columns - 1),
+                Err(_) => println!("Not a valid number, try again."),
+            },
+            ("save", None) | ("load", None) => println!("Please give a file name, try again."),
+            (row, Some(column)) if free => match (row.parse(), column.parse()) {
+                (Ok(row), Ok(column)) if row < num_rows && column < num_columns => {
+                    return TurnInput::Place(row, column)
+                }
+                (Ok(_), Ok(_)) => println!("Not on the board, try again."),
+                _ => println!("Not a valid row and column or command, try again."),
+            },
+            (_, None) if free => println!("Please give a row and a column, try again."),
+            (other, _) => match other.parse() {
+                Ok(n) if n < num_columns => return TurnInput::Column(n),
+                Ok(_) => println!("Not between 0 and {}, try again.", num_columns - 1),
+                Err(_) => println!("Not a valid number or command, try again."),
+            },
+        }
+    }
+}
+
 fn get_yes_no_from_user(message: &str) -> bool {
     println!("Please enter yes or no for {}.", message);
     let mut input = String::new();
@@ -140,6 +868,15 @@ fn get_yes_no_from_user(message: &str) -> bool {
     }
 }
 
+fn get_line_from_user(message: &str) -> String {
+    println!("Please enter {}.", message);
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .expect("failed to read stdin");
+    input.trim().to_string()
+}
+
 fn get_usize_from_user(message: &str) -> usize {
     println!("Please enter a number for {}.", message);
     let mut input = String::new();
@@ -155,6 +892,28 @@ fn get_usize_from_user(message: &str) -> usize {
     }
 }
 
+/// Reads a 256-color terminal color, or `None` if the line is left empty.
+fn get_optional_color_from_user(message: &str) -> Option<u8> {
+    println!(
+        "Please enter a number for {} between 0 and 255, or nothing to keep the current one.",
+        message
+    );
+    let mut input = String::new();
+    loop {
+        input.clear();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("failed to read stdin");
+        match input.trim() {
+            "" => return None,
+            color => match color.parse() {
+                Ok(color) => return Some(color),
+                Err(_) => println!("Not between 0 and 255, try again."),
+            },
+        }
+    }
+}
+
 fn get_usize_from_user_in_range(message: &str, min_val: usize, max_val: usize) -> usize {
     if min_val + 1 > max_val {
         panic!("Impossible range");
@@ -183,3 +942,144 @@ fn get_usize_from_user_in_range(message: &str, min_val: usize, max_val: usize) -
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(flags: &str) -> Result<Option<Args>, ArgsError> {
+        parse_args(flags.split_whitespace().map(String::from))
+    }
+
+    fn parse_ok(flags: &str) -> Args {
+        parse(flags).unwrap().unwrap()
+    }
+
+    #[test]
+    fn no_flags_asks_interactively() {
+        assert!(parse("").unwrap().is_none());
+    }
+
+    #[test]
+    fn values_after_equals_or_space() {
+        let args = parse_ok("--rows=5 --columns 9 --win-rule=exactly --pop-out");
+        assert_eq!(args.num_rows, 5);
+        assert_eq!(args.num_columns, 9);
+        assert_eq!(args.win_rule, WinRule::Exactly);
+        assert!(args.pop_out);
+        assert_eq!(parse_ok("--name 1:Bob").team_info.len(), 1);
+    }
+
+    #[test]
+    fn computer_strength_ranges() {
+        let args = parse_ok(&format!("--ai 0:1 --mcts=1:{}", MAX_PLAYOUTS));
+        assert_eq!(
+            args.computers,
+            vec![
+                (Team::new(0), ComputerSetup::Search(1)),
+                (Team::new(1), ComputerSetup::Mcts(MAX_PLAYOUTS)),
+            ]
+        );
+        let max_depth = format!("--ai 0:{}", ai::MAX_DEPTH);
+        assert_eq!(parse_ok(&max_depth).computers.len(), 1);
+        for flags in &[
+            "--ai 0:0".to_string(),
+            format!("--ai 0:{}", ai::MAX_DEPTH + 1),
+            format!("--mcts 0:{}", MAX_PLAYOUTS + 1),
+            "--ai 0".to_string(),
+            "--ai x:2".to_string(),
+        ] {
+            assert!(
+                matches!(parse(flags), Err(ArgsError::InvalidValue(_, _))),
+                "{}",
+                flags
+            );
+        }
+    }
+
+    #[test]
+    fn missing_values() {
+        assert!(matches!(
+            parse("--rows"),
+            Err(ArgsError::MissingValue(flag)) if flag == "--rows"
+        ));
+        assert!(matches!(
+            parse("--plain --ai"),
+            Err(ArgsError::MissingValue(flag)) if flag == "--ai"
+        ));
+    }
+
+    #[test]
+    fn unknown_flags() {
+        assert!(matches!(
+            parse("--bogus"),
+            Err(ArgsError::UnknownFlag(flag)) if flag == "--bogus"
+        ));
+        assert!(matches!(
+            parse("--bogus --rows 5"),
+            Err(ArgsError::UnknownFlag(flag)) if flag == "--bogus"
+        ));
+        assert!(matches!(
+            parse("--bogus=1"),
+            Err(ArgsError::UnknownFlag(flag)) if flag == "--bogus"
+        ));
+        assert!(matches!(parse("--help"), Err(ArgsError::Help)));
+    }
+
+    #[test]
+    fn ranking_shares_tied_places() {
+        let config = GameConfig {
+            num_teams: 3,
+            num_rows: 1,
+            num_columns: 6,
+            winning_length: 2,
+            scoring: ScoringMode::CountLines,
+            ..GameConfig::default()
+        };
+        let game = GameState::from_moves("124635", &config).unwrap();
+        assert_eq!(
+            ranking_lines(&game),
+            vec![
+                "1. Team 1: 1 line",
+                "1. Team 2: 1 line",
+                "3. Team 0: 0 lines"
+            ]
+        );
+        assert_eq!(
+            outcome_message(&game),
+            "The most lines are tied, it's a draw!"
+        );
+        assert!(ranking_lines(&GameState::default()).is_empty());
+    }
+
+    #[test]
+    fn load_decides_the_game() {
+        assert_eq!(
+            parse_ok("--load game.txt --ai 1:3 --plain").load,
+            Some(PathBuf::from("game.txt"))
+        );
+        for flags in &["--rows 5 --load game.txt", "--load=game.txt --cylinder"] {
+            assert!(
+                matches!(parse(flags), Err(ArgsError::ConflictsWithLoad(_))),
+                "{}",
+                flags
+            );
+        }
+    }
+
+    #[test]
+    fn outcome_messages() {
+        let game = GameState::from_moves("1212121", &GameConfig::default()).unwrap();
+        assert_eq!(outcome_message(&game), "Team 0 wins! (vertical line)");
+
+        let config = GameConfig {
+            win_rule: WinRule::OverlineLoses,
+            ..GameConfig::default()
+        };
+        let game = GameState::from_moves("11223355664", &config).unwrap();
+        assert_eq!(
+            outcome_message(&game),
+            "Team 1 wins because Team 0 made a line longer than 4!"
+        );
+    }
+}
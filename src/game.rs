@@ -1,6 +1,6 @@
 use array2d::Array2D;
 use itertools::iproduct;
-use std::{char, fmt};
+use std::{char, error, fmt, ops::Range};
 
 pub const DEFAULT_FIRST_TURN: Team = Team(0);
 pub const DEFAULT_NUM_TEAMS: usize = 2;
@@ -8,48 +8,401 @@ pub const DEFAULT_WINNING_LENGTH: usize = 4;
 pub const DEFAULT_NUM_ROWS: usize = 6;
 pub const DEFAULT_NUM_COLUMNS: usize = 7;
 
-pub const MAX_PRINTABLE_TEAMS: usize = 16;
-const DEFAULT_EMPTY_CHAR: char = '_';
+pub(crate) const DEFAULT_EMPTY_CHAR: char = '_';
+const MOVE_STRING_RADIX: u32 = 36;
+const TEAM_LABEL_RADIX: u32 = 36;
+/// How many times the same position has to come up in a PopOut game for it
+/// to be a draw.
+const REPETITIONS_TO_DRAW: usize = 3;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Error {
     OutOfBounds,
     ColumnFull,
     NotThatTeamsTurn,
     InvalidTeam,
     GameOver,
+    NothingToUndo,
+    NothingToRedo,
+    UnsupportedBoard,
+    NoTeams,
+    InvalidDimensions,
+    InvalidWinningLength,
+    WrongNumberOfPlayers,
+    InvalidTeamName,
+    InvalidGlyph,
+    InconsistentState,
+    PopOutNotAllowed,
+    CannotPop,
+    UnsupportedMove,
+    DropNotAllowed,
+    PlaceNotAllowed,
+    CellOccupied,
+    IncompatibleRules,
+}
+
+/// A problem with a move string, pointing at the character (counted from
+/// zero) that caused it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MoveStringError {
+    InvalidCharacter(usize, char),
+    InvalidMove(usize, Error),
+    InvalidConfig(Error),
+}
+
+/// How the edges of the board connect.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Topology {
+    /// An ordinary board, where lines stop at the edges.
+    #[default]
+    Flat,
+    /// The left and right edges are joined, so horizontal and diagonal
+    /// lines can wrap from the last column to the first.
+    Cylinder,
+}
+
+/// Where a chip ends up when it is played.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Placement {
+    /// Chips are dropped in a column and fall to the lowest empty cell.
+    #[default]
+    Gravity,
+    /// Chips are placed on any empty cell, as in tic-tac-toe or gomoku.
+    Free,
+}
+
+/// Which runs of chips win the game.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WinRule {
+    /// Any run of `winning_length` or more chips wins.
+    #[default]
+    AtLeast,
+    /// Only a run of exactly `winning_length` chips wins, so longer runs
+    /// (overlines) do not count, as in standard gomoku.
+    Exactly,
+    /// A run of exactly `winning_length` chips wins, but a team that makes
+    /// a longer run without also making an exact one loses. Only for two
+    /// teams.
+    OverlineLoses,
+}
+
+/// When the game ends and who wins it.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScoringMode {
+    /// The first team to make a line wins.
+    #[default]
+    FirstLine,
+    /// Lines do not end the game. Once the board is full, the team with the
+    /// most lines wins, and a tie for the most is a draw.
+    CountLines,
+}
+
+/// The settings a game is created with.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameConfig {
+    pub first_turn: Team,
+    pub num_teams: usize,
+    pub num_rows: usize,
+    pub num_columns: usize,
+    pub winning_length: usize,
+    /// Whether teams may pop their own chips out of the bottom row instead
+    /// of dropping one.
+    pub pop_out: bool,
+    pub topology: Topology,
+    /// Cannot be `Placement::Free` in a PopOut game.
+    pub placement: Placement,
+    /// Can only be `WinRule::OverlineLoses` with two teams.
+    pub win_rule: WinRule,
+    /// `ScoringMode::CountLines` needs `WinRule::AtLeast` and cannot be
+    /// used in a PopOut game.
+    pub scoring: ScoringMode,
+}
+
+impl Default for GameConfig {
+    fn default() -> GameConfig {
+        GameConfig {
+            first_turn: DEFAULT_FIRST_TURN,
+            num_teams: DEFAULT_NUM_TEAMS,
+            num_rows: DEFAULT_NUM_ROWS,
+            num_columns: DEFAULT_NUM_COLUMNS,
+            winning_length: DEFAULT_WINNING_LENGTH,
+            pop_out: false,
+            topology: Topology::Flat,
+            placement: Placement::Gravity,
+            win_rule: WinRule::AtLeast,
+            scoring: ScoringMode::FirstLine,
+        }
+    }
+}
+
+impl GameConfig {
+    /// Checks that the rules can be played together, failing with
+    /// `Error::IncompatibleRules` if not. The board size is checked when a
+    /// game is created.
+    pub fn check_rules(&self) -> Result<(), Error> {
+        if self.pop_out && self.placement == Placement::Free
+            || self.win_rule == WinRule::OverlineLoses && self.num_teams != 2
+            || self.scoring == ScoringMode::CountLines
+                && (self.pop_out || self.win_rule != WinRule::AtLeast)
+        {
+            return Err(Error::IncompatibleRules);
+        }
+        Ok(())
+    }
+}
+
+/// Checks that a board has at least one cell and that a line of
+/// `winning_length` fits on it.
+pub(crate) fn validate_board(
+    num_rows: usize,
+    num_columns: usize,
+    winning_length: usize,
+) -> Result<(), Error> {
+    if num_rows == 0 || num_columns == 0 {
+        return Err(Error::InvalidDimensions);
+    }
+    if winning_length == 0 || winning_length > num_rows.max(num_columns) {
+        return Err(Error::InvalidWinningLength);
+    }
+    Ok(())
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameOutcome {
+    InProgress,
+    Won(Team),
+    Draw,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayedMove {
+    pub team: Team,
+    /// Where the chip landed or was placed, or 0 for a chip popped out of
+    /// the bottom.
+    pub row: usize,
+    pub column: usize,
+    pub kind: MoveKind,
+}
+
+impl PlayedMove {
+    pub fn to_move(self) -> Move {
+        match self.kind {
+            MoveKind::Drop => Move::Drop(self.column),
+            MoveKind::Pop => Move::Pop(self.column),
+            MoveKind::Place => Move::Place(self.row, self.column),
+        }
+    }
+}
+
+/// A move a team can make on its turn.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Move {
+    /// Drop a chip in a column.
+    Drop(usize),
+    /// Pop the team's own chip out of the bottom of a column, moving the
+    /// rest of the column down. Only allowed in PopOut games.
+    Pop(usize),
+    /// Place a chip on an empty cell, given as a row and a column. Only
+    /// allowed in free placement games.
+    Place(usize, usize),
+}
+
+impl Move {
+    pub fn column(self) -> usize {
+        match self {
+            Move::Drop(column) | Move::Pop(column) | Move::Place(_, column) => column,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MoveKind {
+    Drop,
+    Pop,
+    Place,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    Vertical,
+    Horizontal,
+    UpLeft,
+    UpRight,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Vertical,
+        Direction::Horizontal,
+        Direction::UpLeft,
+        Direction::UpRight,
+    ];
+
+    /// The row and column offsets of one step along the line. Rows count up
+    /// from the bottom of the board.
+    pub fn steps(self) -> (isize, isize) {
+        match self {
+            Direction::Vertical => (1, 0),
+            Direction::Horizontal => (0, 1),
+            Direction::UpLeft => (1, -1),
+            Direction::UpRight => (1, 1),
+        }
+    }
+}
+
+/// A run of at least `winning_length` chips, listed from one end to the
+/// other as `(row, column)` coordinates. `team` is the team whose chips
+/// make up the run, which is not the winner for an overline that lost.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WinningLine {
+    pub team: Team,
+    pub direction: Direction,
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// Something that happened to a game, returned by the methods that change
+/// it so that front ends, loggers and the like can react.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameEvent {
+    ChipDropped {
+        team: Team,
+        row: usize,
+        column: usize,
+    },
+    ChipPopped {
+        team: Team,
+        column: usize,
+    },
+    ChipPlaced {
+        team: Team,
+        row: usize,
+        column: usize,
+    },
+    /// It is now `team`'s turn. Not sent once the game is over.
+    TurnChanged {
+        team: Team,
+    },
+    /// Sent once for each line the winning move completed.
+    GameWon {
+        team: Team,
+        line: WinningLine,
+    },
+    GameDrawn,
+    MoveUndone {
+        team: Team,
+        row: usize,
+        column: usize,
+    },
+}
+
+/// How a team is shown to players.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TeamInfo {
+    /// The name used in messages, like "Team 0".
+    pub name: String,
+    /// The symbol drawn for the team's chips on a plain board. It can be
+    /// more than one character, in which case every cell is widened to fit.
+    pub glyph: String,
+    /// A 256-color terminal color code, or `None` to use the palette's color.
+    pub color: Option<u8>,
+}
+
+impl TeamInfo {
+    /// The name, glyph and color a team has unless told otherwise. The glyph
+    /// is the team's number in base 36, so teams past 35 get two characters.
+    pub fn default_for(team: Team) -> TeamInfo {
+        TeamInfo {
+            name: team.to_string(),
+            glyph: team_label(team.0),
+            color: None,
+        }
+    }
 }
 
 type Cell = Option<Team>;
 
+/// Puts a popped chip back at the bottom of a column, moving the rest of
+/// the column up. The top cell of the column must be empty.
+fn unpop_cells(cells: &mut Array2D<Cell>, team: Team, column: usize) {
+    for row in (1..cells.num_rows()).rev() {
+        cells[(row, column)] = cells[(row - 1, column)];
+    }
+    cells[(0, column)] = Some(team);
+}
+
+fn team_label(mut index: usize) -> String {
+    let radix = TEAM_LABEL_RADIX as usize;
+    let mut digits = Vec::new();
+    loop {
+        digits.push(char::from_digit((index % radix) as u32, TEAM_LABEL_RADIX).unwrap());
+        index /= radix;
+        if index == 0 {
+            break;
+        }
+    }
+    digits.into_iter().rev().collect()
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Team(usize);
 
 impl Team {
     pub fn new(team: usize) -> Team {
         Team(team)
     }
+
+    pub(crate) fn index(self) -> usize {
+        self.0
+    }
 }
 
+#[derive(Clone)]
 pub struct GameState {
     cells: Array2D<Cell>,
+    first_turn: Team,
     cur_turn: Team,
     num_teams: usize,
     winning_length: usize,
+    history: Vec<PlayedMove>,
+    undone: Vec<PlayedMove>,
+    /// How many chips were on the board before the first move in `history`.
+    starting_chips: usize,
+    outcome: GameOutcome,
+    winning_lines: Vec<WinningLine>,
+    team_info: Vec<TeamInfo>,
+    pop_out: bool,
+    topology: Topology,
+    placement: Placement,
+    win_rule: WinRule,
+    scoring: ScoringMode,
+    /// Every position of a PopOut game so far, to spot repetitions. Empty
+    /// in other games, where positions cannot repeat.
+    positions: Vec<(Vec<Cell>, Team)>,
 }
 
-impl GameState {
-    pub fn default() -> GameState {
-        GameState::new(
-            DEFAULT_FIRST_TURN,
-            DEFAULT_NUM_TEAMS,
-            DEFAULT_NUM_ROWS,
-            DEFAULT_NUM_COLUMNS,
-            DEFAULT_WINNING_LENGTH,
-        )
-        .unwrap()
+impl Default for GameState {
+    fn default() -> GameState {
+        GameState::from_config(&GameConfig::default()).unwrap()
     }
+}
 
+impl GameState {
     pub fn new(
         first_turn: Team,
         num_teams: usize,
@@ -57,19 +410,170 @@ impl GameState {
         num_columns: usize,
         winning_length: usize,
     ) -> Result<GameState, Error> {
-        if first_turn.0 > num_teams {
+        if num_teams == 0 {
+            return Err(Error::NoTeams);
+        }
+        if first_turn.0 >= num_teams {
             return Err(Error::InvalidTeam);
         }
+        validate_board(num_rows, num_columns, winning_length)?;
         let rows = GameState::create_empty_grid_rows(num_rows, num_columns);
         Ok(GameState {
             cells: Array2D::from_rows(&rows),
             num_teams,
+            first_turn,
             cur_turn: first_turn,
             winning_length,
+            history: Vec::new(),
+            undone: Vec::new(),
+            starting_chips: 0,
+            outcome: GameOutcome::InProgress,
+            winning_lines: Vec::new(),
+            team_info: GameState::default_team_info(num_teams),
+            pop_out: false,
+            topology: Topology::Flat,
+            placement: Placement::Gravity,
+            win_rule: WinRule::AtLeast,
+            scoring: ScoringMode::FirstLine,
+            positions: Vec::new(),
         })
     }
 
-    #[allow(dead_code)]
+    pub fn from_config(config: &GameConfig) -> Result<GameState, Error> {
+        config.check_rules()?;
+        let mut game = GameState::new(
+            config.first_turn,
+            config.num_teams,
+            config.num_rows,
+            config.num_columns,
+            config.winning_length,
+        )?;
+        game.set_pop_out(config.pop_out);
+        game.topology = config.topology;
+        game.placement = config.placement;
+        game.win_rule = config.win_rule;
+        game.scoring = config.scoring;
+        Ok(game)
+    }
+
+    /// Plays a sequence of columns numbered from one, such as `"4453"`.
+    /// Columns past 9 are written as letters, so `a` is column 10.
+    pub fn from_moves(moves: &str, config: &GameConfig) -> Result<GameState, MoveStringError> {
+        GameState::from_moves_with_offset(moves, config, 1)
+    }
+
+    /// Plays a sequence of columns numbered from zero, such as `"3342"`.
+    pub fn from_moves_zero_based(
+        moves: &str,
+        config: &GameConfig,
+    ) -> Result<GameState, MoveStringError> {
+        GameState::from_moves_with_offset(moves, config, 0)
+    }
+
+    /// Builds a game with the settings in `config` that starts with the chips
+    /// in `cells`, as if they had been played in turn just before
+    /// `first_turn`. Fails with `Error::InconsistentState` if they could not
+    /// have been.
+    pub(crate) fn from_starting_cells(
+        config: &GameConfig,
+        cells: Array2D<Cell>,
+    ) -> Result<GameState, Error> {
+        let mut game = GameState::from_config(config)?;
+        if cells.num_rows() != config.num_rows || cells.num_columns() != config.num_columns {
+            return Err(Error::InvalidDimensions);
+        }
+        let mut counts = vec![0; config.num_teams];
+        for team in cells.elements_row_major_iter().flatten() {
+            *counts.get_mut(team.0).ok_or(Error::InvalidTeam)? += 1;
+        }
+        let total: usize = counts.iter().sum();
+        let (rounds, extra) = (total / config.num_teams, total % config.num_teams);
+        let in_turn = counts.iter().enumerate().all(|(team, &count)| {
+            let turns_before =
+                (config.first_turn.0 + config.num_teams - 1 - team) % config.num_teams;
+            count == rounds + usize::from(turns_before < extra)
+        });
+        let floating = iproduct!(1..config.num_rows, 0..config.num_columns).any(|(row, column)| {
+            cells[(row, column)].is_some() && cells[(row - 1, column)].is_none()
+        });
+        if !in_turn || floating && config.placement == Placement::Gravity {
+            return Err(Error::InconsistentState);
+        }
+        game.cells = cells;
+        game.starting_chips = total;
+        game.set_pop_out(config.pop_out);
+        game.scan_outcome();
+        Ok(game)
+    }
+
+    /// Builds a game from an already populated grid. The resulting game has
+    /// no move history, so it is treated as if `cur_turn` went first.
+    pub(crate) fn from_cells(
+        cells: Array2D<Cell>,
+        cur_turn: Team,
+        num_teams: usize,
+        winning_length: usize,
+    ) -> GameState {
+        let starting_chips = cells.elements_row_major_iter().flatten().count();
+        let mut game = GameState {
+            cells,
+            first_turn: cur_turn,
+            num_teams,
+            cur_turn,
+            winning_length,
+            history: Vec::new(),
+            undone: Vec::new(),
+            starting_chips,
+            outcome: GameOutcome::InProgress,
+            winning_lines: Vec::new(),
+            team_info: GameState::default_team_info(num_teams),
+            pop_out: false,
+            topology: Topology::Flat,
+            placement: Placement::Gravity,
+            win_rule: WinRule::AtLeast,
+            scoring: ScoringMode::FirstLine,
+            positions: Vec::new(),
+        };
+        game.scan_outcome();
+        game
+    }
+
+    pub fn config(&self) -> GameConfig {
+        GameConfig {
+            first_turn: self.first_turn,
+            num_teams: self.num_teams,
+            num_rows: self.num_rows(),
+            num_columns: self.num_columns(),
+            winning_length: self.winning_length,
+            pop_out: self.pop_out,
+            topology: self.topology,
+            placement: self.placement,
+            win_rule: self.win_rule,
+            scoring: self.scoring,
+        }
+    }
+
+    /// Whether teams may pop their own chips out of the bottom row.
+    pub fn pop_out(&self) -> bool {
+        self.pop_out
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn placement(&self) -> Placement {
+        self.placement
+    }
+
+    pub fn win_rule(&self) -> WinRule {
+        self.win_rule
+    }
+
+    pub fn scoring(&self) -> ScoringMode {
+        self.scoring
+    }
+
     pub fn num_rows(&self) -> usize {
         self.cells.num_rows()
     }
@@ -78,102 +582,589 @@ impl GameState {
         self.cells.num_columns()
     }
 
+    pub fn num_teams(&self) -> usize {
+        self.num_teams
+    }
+
+    pub fn winning_length(&self) -> usize {
+        self.winning_length
+    }
+
+    pub fn first_turn(&self) -> Team {
+        self.first_turn
+    }
+
     pub fn cur_turn(&self) -> Team {
         self.cur_turn
     }
 
-    pub fn drop_chip(&mut self, team: Team, column: usize) -> Result<(), Error> {
+    /// Drops a chip for `team`, returning what happened: the chip landing,
+    /// then either the next turn or how the game ended.
+    pub fn drop_chip(&mut self, team: Team, column: usize) -> Result<Vec<GameEvent>, Error> {
+        self.play_move(team, Move::Drop(column))
+    }
+
+    /// Pops `team`'s chip out of the bottom of `column` in a PopOut game.
+    /// If that completes lines for more than one team, the team that popped
+    /// wins when it has a line of its own.
+    pub fn pop_chip(&mut self, team: Team, column: usize) -> Result<Vec<GameEvent>, Error> {
+        self.play_move(team, Move::Pop(column))
+    }
+
+    /// Places a chip for `team` on any empty cell in a free placement game.
+    pub fn place_chip(
+        &mut self,
+        team: Team,
+        row: usize,
+        column: usize,
+    ) -> Result<Vec<GameEvent>, Error> {
+        self.play_move(team, Move::Place(row, column))
+    }
+
+    /// Makes any kind of move for `team`, returning what happened as
+    /// `drop_chip` does.
+    pub fn play_move(&mut self, team: Team, mv: Move) -> Result<Vec<GameEvent>, Error> {
         if self.game_over() {
             return Err(Error::GameOver);
         }
         if self.cur_turn != team {
             return Err(Error::NotThatTeamsTurn);
         }
-        self.drop_chip_cells(column)?;
-        self.cur_turn = self.next_turn();
-        Ok(())
+        let events = self.apply_move(mv)?;
+        self.undone.clear();
+        Ok(events)
+    }
+
+    pub fn undo(&mut self) -> Result<Vec<GameEvent>, Error> {
+        let played = self.history.pop().ok_or(Error::NothingToUndo)?;
+        match played.kind {
+            MoveKind::Drop | MoveKind::Place => self.cells[(played.row, played.column)] = None,
+            MoveKind::Pop => unpop_cells(&mut self.cells, played.team, played.column),
+        }
+        self.cur_turn = played.team;
+        self.positions.pop();
+        // A finished game can only have ended on its last move
+        self.outcome = GameOutcome::InProgress;
+        self.winning_lines.clear();
+        self.undone.push(played);
+        Ok(vec![
+            GameEvent::MoveUndone {
+                team: played.team,
+                row: played.row,
+                column: played.column,
+            },
+            GameEvent::TurnChanged { team: played.team },
+        ])
+    }
+
+    pub fn redo(&mut self) -> Result<Vec<GameEvent>, Error> {
+        let played = self.undone.pop().ok_or(Error::NothingToRedo)?;
+        self.apply_move(played.to_move())
+    }
+
+    pub fn history(&self) -> &[PlayedMove] {
+        &self.history
     }
 
     pub fn game_over(&self) -> bool {
-        (0..self.num_teams).any(|team_num| self.has_won(Team(team_num)))
+        self.outcome() != GameOutcome::InProgress
+    }
+
+    pub fn outcome(&self) -> GameOutcome {
+        self.outcome
     }
 
     pub fn has_won(&self, team: Team) -> bool {
-        self.has_won_vertically(team)
-            || self.has_won_horizontally(team)
-            || self.has_won_diagonally(team)
+        self.who_won() == Some(team)
     }
 
     pub fn who_won(&self) -> Option<Team> {
-        (0..self.num_teams)
-            .map(Team)
-            .find(|&team| self.has_won(team))
+        match self.outcome {
+            GameOutcome::Won(team) => Some(team),
+            _ => None,
+        }
+    }
+
+    /// The columns played so far, numbered from one as in `from_moves`. Fails
+    /// with `Error::UnsupportedBoard` if a column is too large for a single
+    /// character.
+    pub fn to_move_string(&self) -> Result<String, Error> {
+        self.to_move_string_with_offset(1)
     }
 
+    /// The columns played so far, numbered from zero.
+    pub fn to_move_string_zero_based(&self) -> Result<String, Error> {
+        self.to_move_string_with_offset(0)
+    }
+
+    /// The lines that ended the game. A single chip can complete several
+    /// lines at once, so there may be more than one. Under
+    /// `WinRule::OverlineLoses` these are the loser's overlines if that is
+    /// how the game ended, and under `ScoringMode::CountLines` they are all
+    /// of the winner's lines.
+    pub fn winning_lines(&self) -> &[WinningLine] {
+        &self.winning_lines
+    }
+
+    /// Each row of the board from the bottom up, with every cell written as
+    /// `cell_width` characters. Chips are right aligned.
     pub fn to_string_arr(&self) -> Vec<String> {
         self.cells
             .rows_iter()
-            .map(|row_iter| {
-                row_iter
-                    .map(|&cell| GameState::cell_to_char(cell))
-                    .collect()
+            .map(|row_iter| row_iter.map(|&cell| self.cell_to_string(cell)).collect())
+            .collect()
+    }
+
+    /// How many characters each cell takes in `to_string_arr`: the length of
+    /// the longest team glyph.
+    pub fn cell_width(&self) -> usize {
+        self.team_info
+            .iter()
+            .map(|info| info.glyph.chars().count())
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// How the given team is shown to players.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the team is not playing in this game.
+    pub fn team_info(&self, team: Team) -> &TeamInfo {
+        &self.team_info[team.0]
+    }
+
+    /// The name used for the given team in messages.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the team is not playing in this game.
+    pub fn team_name(&self, team: Team) -> &str {
+        &self.team_info(team).name
+    }
+
+    /// Changes how a team is shown. The name must be a single non-blank
+    /// line, and the glyph must have no spaces, not be made only of
+    /// underscores like an empty cell, and differ from the other teams'
+    /// glyphs.
+    pub fn set_team_info(&mut self, team: Team, info: TeamInfo) -> Result<(), Error> {
+        if team.0 >= self.num_teams {
+            return Err(Error::InvalidTeam);
+        }
+        let mut team_info = self.team_info.clone();
+        team_info[team.0] = info;
+        GameState::validate_team_info(&team_info)?;
+        self.team_info = team_info;
+        Ok(())
+    }
+
+    fn validate_team_info(team_info: &[TeamInfo]) -> Result<(), Error> {
+        for (index, info) in team_info.iter().enumerate() {
+            if info.name.trim().is_empty() || info.name.contains('\n') {
+                return Err(Error::InvalidTeamName);
+            }
+            let glyph_visible = !info.glyph.is_empty()
+                && !info.glyph.chars().any(char::is_whitespace)
+                && !info.glyph.chars().all(|c| c == DEFAULT_EMPTY_CHAR);
+            let glyph_taken = team_info[..index]
+                .iter()
+                .any(|other| other.glyph == info.glyph);
+            if !glyph_visible || glyph_taken {
+                return Err(Error::InvalidGlyph);
+            }
+        }
+        Ok(())
+    }
+
+    /// The team whose chip is at `(row, column)`, counting rows from the
+    /// bottom. `None` if the cell is empty or off the board.
+    pub fn cell(&self, row: usize, column: usize) -> Option<Team> {
+        self.cells.get(row, column).cloned().flatten()
+    }
+
+    /// The columns a chip can be dropped in, or none if the game is over
+    /// or chips are placed freely.
+    pub fn legal_moves(&self) -> Vec<usize> {
+        if self.game_over() || self.placement == Placement::Free {
+            return Vec::new();
+        }
+        let top_row = self.num_rows() - 1;
+        (0..self.num_columns())
+            .filter(|&column| self.cells[(top_row, column)].is_none())
+            .collect()
+    }
+
+    /// How many chips are in a column, which is also the row the next chip
+    /// dropped there lands in.
+    pub fn column_height(&self, column: usize) -> Result<usize, Error> {
+        if column >= self.num_columns() {
+            return Err(Error::OutOfBounds);
+        }
+        Ok(self
+            .cells
+            .column_iter(column)
+            .take_while(|cell| cell.is_some())
+            .count())
+    }
+
+    /// The columns the team whose turn it is can pop a chip out of, or none
+    /// if the game is over or is not a PopOut game.
+    pub fn legal_pops(&self) -> Vec<usize> {
+        if self.game_over() {
+            return Vec::new();
+        }
+        self.pop_columns()
+    }
+
+    /// The empty cells as `(row, column)` in a free placement game, or none
+    /// if the game is over or chips are dropped.
+    pub fn legal_places(&self) -> Vec<(usize, usize)> {
+        if self.game_over() || self.placement == Placement::Gravity {
+            return Vec::new();
+        }
+        iproduct!(0..self.num_rows(), 0..self.num_columns())
+            .filter(|&cell| self.cells[cell].is_none())
+            .collect()
+    }
+
+    /// Every move the team whose turn it is can make: the drops, then the
+    /// pops, then the places.
+    pub fn available_moves(&self) -> Vec<Move> {
+        let drops = self.legal_moves().into_iter().map(Move::Drop);
+        let pops = self.legal_pops().into_iter().map(Move::Pop);
+        let places = self
+            .legal_places()
+            .into_iter()
+            .map(|(row, column)| Move::Place(row, column));
+        drops.chain(pops).chain(places).collect()
+    }
+
+    /// Whether every cell of the board has a chip in it.
+    pub fn is_full(&self) -> bool {
+        self.cells.elements_row_major_iter().all(Option::is_some)
+    }
+
+    /// How many moves have been played, counting each chip the game started
+    /// with as a move. Pops count as moves even though they take a chip off
+    /// the board.
+    pub fn moves_played(&self) -> usize {
+        self.starting_chips + self.history.len()
+    }
+
+    /// The game after the team whose turn it is drops a chip in `column`,
+    /// leaving this one unchanged.
+    pub fn play(&self, column: usize) -> Result<GameState, Error> {
+        let mut game = self.clone();
+        game.drop_chip(self.cur_turn, column)?;
+        Ok(game)
+    }
+
+    /// How many distinct lines of `winning_length` chips each team has, in
+    /// team order. Overlapping lines each count, so a run one chip longer
+    /// than `winning_length` is two lines.
+    pub fn scores(&self) -> Vec<(Team, usize)> {
+        (0..self.num_teams)
+            .map(Team)
+            .map(|team| (team, self.scored_lines(team).count()))
+            .collect()
+    }
+
+    /// Every line of exactly `winning_length` cells that `team` fills.
+    fn scored_lines(&self, team: Team) -> impl Iterator<Item = WinningLine> + '_ {
+        self.directed_windows()
+            .filter(move |(_, cells)| cells.iter().all(|&cell| self.cells[cell] == Some(team)))
+            .map(move |(direction, cells)| WinningLine {
+                team,
+                direction,
+                cells,
             })
+    }
+
+    pub(crate) fn windows(&self) -> impl Iterator<Item = Vec<(usize, usize)>> + '_ {
+        self.directed_windows().map(|(_, cells)| cells)
+    }
+
+    /// Every window of `winning_length` cells with the direction it runs in.
+    /// The `diagonal_up_left` windows climb to the right, so they are
+    /// `Direction::UpRight`, and the other way around.
+    fn directed_windows(&self) -> impl Iterator<Item = (Direction, Vec<(usize, usize)>)> + '_ {
+        let vertical = self
+            .vertical_starting_coordinates()
+            .map(move |(row, column)| {
+                let cells = self.vertical_sequence_coordinates(row, column).collect();
+                (Direction::Vertical, cells)
+            });
+        let horizontal = self
+            .horizontal_starting_coordinates()
+            .map(move |(row, column)| {
+                let cells = self.horizontal_sequence_coordinates(row, column).collect();
+                (Direction::Horizontal, cells)
+            });
+        let up_left = self
+            .diagonal_up_left_starting_coordinates()
+            .map(move |(row, column)| {
+                let cells = self
+                    .diagonal_up_left_sequence_coordinates(row, column)
+                    .collect();
+                (Direction::UpRight, cells)
+            });
+        let up_right = self
+            .diagonal_up_right_starting_coordinates()
+            .map(move |(row, column)| {
+                let cells = self
+                    .diagonal_up_right_sequence_coordinates(row, column)
+                    .collect();
+                (Direction::UpLeft, cells)
+            });
+        vertical.chain(horizontal).chain(up_left).chain(up_right)
+    }
+
+    fn from_moves_with_offset(
+        moves: &str,
+        config: &GameConfig,
+        offset: u32,
+    ) -> Result<GameState, MoveStringError> {
+        let mut game = GameState::from_config(config).map_err(MoveStringError::InvalidConfig)?;
+        for (index, c) in moves.chars().enumerate() {
+            let column = c
+                .to_digit(MOVE_STRING_RADIX)
+                .and_then(|digit| digit.checked_sub(offset))
+                .ok_or(MoveStringError::InvalidCharacter(index, c))?;
+            let team = game.cur_turn;
+            game.drop_chip(team, column as usize)
+                .map_err(|e| MoveStringError::InvalidMove(index, e))?;
+        }
+        Ok(game)
+    }
+
+    fn to_move_string_with_offset(&self, offset: u32) -> Result<String, Error> {
+        if self
+            .history
+            .iter()
+            .any(|played| played.kind != MoveKind::Drop)
+        {
+            return Err(Error::UnsupportedMove);
+        }
+        self.history
+            .iter()
+            .map(|played| char::from_digit(played.column as u32 + offset, MOVE_STRING_RADIX))
+            .collect::<Option<String>>()
+            .ok_or(Error::UnsupportedBoard)
+    }
+
+    fn set_pop_out(&mut self, pop_out: bool) {
+        self.pop_out = pop_out;
+        self.positions.clear();
+        if pop_out {
+            self.positions.push(self.position());
+        }
+    }
+
+    fn position(&self) -> (Vec<Cell>, Team) {
+        (self.cells.as_row_major(), self.cur_turn)
+    }
+
+    /// The columns whose bottom chip belongs to the team whose turn it is.
+    fn pop_columns(&self) -> Vec<usize> {
+        if !self.pop_out {
+            return Vec::new();
+        }
+        (0..self.num_columns())
+            .filter(|&column| self.cells[(0, column)] == Some(self.cur_turn))
             .collect()
     }
 
-    fn has_won_vertically(&self, team: Team) -> bool {
-        self.vertical_starting_coordinates()
-            .map(|(row, column)| self.vertical_sequence_coordinates(row, column))
-            .any(|coordinates| self.won_with_coordinates(coordinates, team))
+    /// Every line through the cells of a column, for after a pop has moved
+    /// them all. Only the lines of one team are kept: the team that popped
+    /// if it has any, otherwise the next team in turn order that does.
+    fn lines_after_pop(&self, popper: Team, column: usize) -> Vec<WinningLine> {
+        let mut lines: Vec<WinningLine> = Vec::new();
+        for row in 0..self.num_rows() {
+            for line in self.lines_through(row, column) {
+                if !lines.contains(&line) {
+                    lines.push(line);
+                }
+            }
+        }
+        let winner = (0..self.num_teams)
+            .map(|offset| Team((popper.0 + offset) % self.num_teams))
+            .find(|&team| lines.iter().any(|line| line.team == team));
+        lines.retain(|line| Some(line.team) == winner);
+        lines
+    }
+
+    fn is_draw(&self) -> bool {
+        if !self.pop_out {
+            return self.is_full();
+        }
+        let position = self.position();
+        let repetitions = self.positions.iter().filter(|&p| *p == position).count();
+        repetitions >= REPETITIONS_TO_DRAW || (self.is_full() && self.pop_columns().is_empty())
+    }
+
+    /// Sets the outcome of an arbitrary grid, checking every occupied cell.
+    fn scan_outcome(&mut self) {
+        let lines = iproduct!(0..self.num_rows(), 0..self.num_columns())
+            .map(|(row, column)| self.lines_through(row, column))
+            .find(|lines| !lines.is_empty())
+            .unwrap_or_default();
+        self.set_outcome(lines);
+    }
+
+    fn set_outcome(&mut self, mut winning_lines: Vec<WinningLine>) {
+        if self.scoring == ScoringMode::CountLines {
+            self.set_counted_outcome();
+            return;
+        }
+        let winning_length = self.winning_length;
+        let is_exact = |line: &WinningLine| line.cells.len() == winning_length;
+        let overline_loses = self.win_rule == WinRule::OverlineLoses;
+        if overline_loses && winning_lines.iter().any(is_exact) {
+            // An exact line wins even if the same move made an overline
+            winning_lines.retain(is_exact);
+        }
+        self.outcome = if let Some(line) = winning_lines.first() {
+            if overline_loses && !is_exact(line) {
+                GameOutcome::Won(Team((line.team.0 + 1) % self.num_teams))
+            } else {
+                GameOutcome::Won(line.team)
+            }
+        } else if self.is_draw() {
+            GameOutcome::Draw
+        } else {
+            GameOutcome::InProgress
+        };
+        self.winning_lines = winning_lines;
+    }
+
+    /// Ends the game once the board is full, with the team that has the
+    /// most lines winning.
+    fn set_counted_outcome(&mut self) {
+        self.winning_lines = Vec::new();
+        if !self.is_full() {
+            self.outcome = GameOutcome::InProgress;
+            return;
+        }
+        let scores = self.scores();
+        let best = scores.iter().map(|&(_, score)| score).max().unwrap_or(0);
+        let mut leaders = scores.iter().filter(|&&(_, score)| score == best);
+        self.outcome = match (leaders.next(), leaders.next()) {
+            (Some(&(team, _)), None) if best > 0 => {
+                self.winning_lines = self.scored_lines(team).collect();
+                GameOutcome::Won(team)
+            }
+            _ => GameOutcome::Draw,
+        };
     }
 
-    fn has_won_horizontally(&self, team: Team) -> bool {
-        self.horizontal_starting_coordinates()
-            .map(|(row, column)| self.horizontal_sequence_coordinates(row, column))
-            .any(|coordinates| self.won_with_coordinates(coordinates, team))
+    fn lines_through(&self, row: usize, column: usize) -> Vec<WinningLine> {
+        let team = match self.cells[(row, column)] {
+            Some(team) => team,
+            None => return Vec::new(),
+        };
+        Direction::ALL
+            .iter()
+            .filter_map(|&direction| {
+                let (row_step, column_step) = direction.steps();
+                let forward = self.run_length(row, column, row_step, column_step, team);
+                let mut backward = self.run_length(row, column, -row_step, -column_step, team);
+                if direction == Direction::Horizontal && self.topology == Topology::Cylinder {
+                    // A full row wraps onto itself, so count each chip once
+                    backward = backward.min(self.num_columns() - 1 - forward);
+                }
+                let length = forward + backward + 1;
+                let is_overline = length > self.winning_length;
+                if length < self.winning_length || is_overline && self.win_rule == WinRule::Exactly
+                {
+                    return None;
+                }
+                let cells = (-(backward as isize)..=forward as isize)
+                    .map(|offset| {
+                        (
+                            (row as isize + offset * row_step) as usize,
+                            self.wrap_column(column as isize + offset * column_step),
+                        )
+                    })
+                    .collect();
+                Some(WinningLine {
+                    team,
+                    direction,
+                    cells,
+                })
+            })
+            .collect()
     }
 
-    fn has_won_diagonally(&self, team: Team) -> bool {
-        self.has_won_diagonally_up_left(team) || self.has_won_diagonally_up_right(team)
-        // self.cells
-        //     .as_rows()
-        //     .windows(self.winning_length)
-        //     .any(|rows| {
-        //         (0..self.cells.num_columns() - self.winning_length + 1).any(|offset| {
-        //             rows.iter()
-        //                 .enumerate()
-        //                 .all(|(index, row)| row[offset + index] == Some(team))
-        //                 || rows.iter().enumerate().all(|(index, row)| {
-        //                     row[offset + self.winning_length - index - 1] == Some(team)
-        //                 })
-        //         })
-        //     })
+    /// How many consecutive chips belonging to `team` there are starting next
+    /// to `(row, column)` and moving by the given steps, stopping before
+    /// coming back around to `(row, column)`.
+    fn run_length(
+        &self,
+        row: usize,
+        column: usize,
+        row_step: isize,
+        column_step: isize,
+        team: Team,
+    ) -> usize {
+        let mut length = 0;
+        let mut coords = (row, column);
+        while let Some(next) = self.step(coords, row_step, column_step) {
+            if self.cells[next] != Some(team) || next == (row, column) {
+                break;
+            }
+            length += 1;
+            coords = next;
+        }
+        length
+    }
+
+    fn step(
+        &self,
+        (row, column): (usize, usize),
+        row_step: isize,
+        column_step: isize,
+    ) -> Option<(usize, usize)> {
+        let row = row as isize + row_step;
+        let column = column as isize + column_step;
+        if self.topology == Topology::Flat && (column < 0 || column as usize >= self.num_columns())
+        {
+            return None;
+        }
+        if row < 0 || row as usize >= self.num_rows() {
+            return None;
+        }
+        Some((row as usize, self.wrap_column(column)))
     }
 
-    fn has_won_diagonally_up_left(&self, team: Team) -> bool {
-        self.diagonal_up_left_starting_coordinates()
-            .map(|(row, column)| self.diagonal_up_left_sequence_coordinates(row, column))
-            .any(|coordinates| self.won_with_coordinates(coordinates, team))
+    /// Brings a column that has gone past either edge back onto a
+    /// cylindrical board. Columns on a flat board are left alone.
+    fn wrap_column(&self, column: isize) -> usize {
+        match self.topology {
+            Topology::Flat => column as usize,
+            Topology::Cylinder => column.rem_euclid(self.num_columns() as isize) as usize,
+        }
     }
 
-    fn has_won_diagonally_up_right(&self, team: Team) -> bool {
-        self.diagonal_up_right_starting_coordinates()
-            .map(|(row, column)| self.diagonal_up_right_sequence_coordinates(row, column))
-            .any(|coordinates| self.won_with_coordinates(coordinates, team))
+    /// The indices a window of `winning_length` cells can start at along a
+    /// side of the board with `size` cells.
+    fn window_starts(&self, size: usize) -> Range<usize> {
+        0..(size + 1).saturating_sub(self.winning_length)
     }
 
-    fn won_with_coordinates<I>(&self, mut coordinates: I, team: Team) -> bool
-    where
-        I: Iterator<Item = (usize, usize)>,
-    {
-        coordinates.all(|coords| self.cells[coords] == Some(team))
+    /// The columns a horizontal or diagonal window can start at. On a
+    /// cylinder that is every column, except that a horizontal window as
+    /// wide as the board is the same wherever it starts.
+    fn column_window_starts(&self, direction: Direction) -> Range<usize> {
+        let num_columns = self.num_columns();
+        match self.topology {
+            Topology::Cylinder if direction != Direction::Horizontal => 0..num_columns,
+            Topology::Cylinder if self.winning_length < num_columns => 0..num_columns,
+            _ => self.window_starts(num_columns),
+        }
     }
 
     fn vertical_starting_coordinates(&self) -> impl Iterator<Item = (usize, usize)> {
-        iproduct!(
-            0..=(self.num_rows() - self.winning_length),
-            0..self.num_columns()
-        )
+        iproduct!(self.window_starts(self.num_rows()), 0..self.num_columns())
     }
 
     fn vertical_sequence_coordinates(
@@ -188,7 +1179,7 @@ impl GameState {
     fn horizontal_starting_coordinates(&self) -> impl Iterator<Item = (usize, usize)> {
         iproduct!(
             0..self.num_rows(),
-            0..=(self.num_columns() - self.winning_length)
+            self.column_window_starts(Direction::Horizontal)
         )
     }
 
@@ -196,15 +1187,15 @@ impl GameState {
         &self,
         row: usize,
         column: usize,
-    ) -> impl Iterator<Item = (usize, usize)> {
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
         let last_column = column + self.winning_length;
-        (column..last_column).map(move |c| (row, c))
+        (column..last_column).map(move |c| (row, self.wrap_column(c as isize)))
     }
 
     fn diagonal_up_left_starting_coordinates(&self) -> impl Iterator<Item = (usize, usize)> {
         iproduct!(
-            0..=(self.num_rows() - self.winning_length),
-            0..=(self.num_columns() - self.winning_length)
+            self.window_starts(self.num_rows()),
+            self.column_window_starts(Direction::UpLeft)
         )
     }
 
@@ -212,16 +1203,18 @@ impl GameState {
         &self,
         row: usize,
         column: usize,
-    ) -> impl Iterator<Item = (usize, usize)> {
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
         let last_row = row + self.winning_length;
         let last_column = column + self.winning_length;
-        (row..last_row).zip(column..last_column)
+        (row..last_row)
+            .zip(column..last_column)
+            .map(move |(r, c)| (r, self.wrap_column(c as isize)))
     }
 
     fn diagonal_up_right_starting_coordinates(&self) -> impl Iterator<Item = (usize, usize)> {
         iproduct!(
-            self.winning_length..self.num_rows(),
-            0..=(self.num_columns() - self.winning_length)
+            self.winning_length..=self.num_rows(),
+            self.column_window_starts(Direction::UpRight)
         )
     }
 
@@ -229,45 +1222,138 @@ impl GameState {
         &self,
         row: usize,
         column: usize,
-    ) -> impl Iterator<Item = (usize, usize)> {
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
         let first_row = row - self.winning_length;
         let last_column = column + self.winning_length;
-        (first_row..row).rev().zip(column..last_column)
+        (first_row..row)
+            .rev()
+            .zip(column..last_column)
+            .map(move |(r, c)| (r, self.wrap_column(c as isize)))
     }
 
-    fn cell_to_char(cell: Cell) -> char {
-        if let Some(team) = cell {
-            if team.0 > MAX_PRINTABLE_TEAMS {
-                panic!("Cannot convert team {} to a char", team);
-            }
-        }
+    fn cell_to_string(&self, cell: Cell) -> String {
+        let width = self.cell_width();
         match cell {
-            Some(team) => char::from_digit(team.0 as u32, 16).unwrap(),
-            None => DEFAULT_EMPTY_CHAR,
+            Some(team) => format!("{:>width$}", self.team_info[team.0].glyph, width = width),
+            None => DEFAULT_EMPTY_CHAR.to_string().repeat(width),
         }
     }
 
+    fn default_team_info(num_teams: usize) -> Vec<TeamInfo> {
+        (0..num_teams)
+            .map(Team)
+            .map(TeamInfo::default_for)
+            .collect()
+    }
+
     fn next_turn(&self) -> Team {
         let next_team_num = (self.cur_turn.0 + 1) % self.num_teams;
         Team(next_team_num)
     }
 
-    fn drop_chip_cells(&mut self, column: usize) -> Result<(), Error> {
-        if column >= self.cells.num_columns() {
+    /// Plays a move for the team whose turn it is, without checking whether
+    /// the game is over.
+    fn apply_move(&mut self, mv: Move) -> Result<Vec<GameEvent>, Error> {
+        let team = self.cur_turn;
+        let (played, lines, first_event) = match mv {
+            Move::Drop(column) => {
+                let row = self.drop_chip_cells(column)?;
+                let played = PlayedMove {
+                    team,
+                    row,
+                    column,
+                    kind: MoveKind::Drop,
+                };
+                let event = GameEvent::ChipDropped { team, row, column };
+                (played, self.lines_through(row, column), event)
+            }
+            Move::Pop(column) => {
+                self.pop_chip_cells(column)?;
+                let played = PlayedMove {
+                    team,
+                    row: 0,
+                    column,
+                    kind: MoveKind::Pop,
+                };
+                let event = GameEvent::ChipPopped { team, column };
+                (played, self.lines_after_pop(team, column), event)
+            }
+            Move::Place(row, column) => {
+                self.place_chip_cells(row, column)?;
+                let played = PlayedMove {
+                    team,
+                    row,
+                    column,
+                    kind: MoveKind::Place,
+                };
+                let event = GameEvent::ChipPlaced { team, row, column };
+                (played, self.lines_through(row, column), event)
+            }
+        };
+        self.history.push(played);
+        self.cur_turn = self.next_turn();
+        if self.pop_out {
+            self.positions.push(self.position());
+        }
+        self.set_outcome(lines);
+        let mut events = vec![first_event];
+        match self.outcome {
+            GameOutcome::Won(winner) => {
+                events.extend(self.winning_lines.iter().map(|line| GameEvent::GameWon {
+                    team: winner,
+                    line: line.clone(),
+                }))
+            }
+            GameOutcome::Draw => events.push(GameEvent::GameDrawn),
+            GameOutcome::InProgress => events.push(GameEvent::TurnChanged {
+                team: self.cur_turn,
+            }),
+        }
+        Ok(events)
+    }
+
+    fn pop_chip_cells(&mut self, column: usize) -> Result<(), Error> {
+        if !self.pop_out {
+            return Err(Error::PopOutNotAllowed);
+        }
+        if column >= self.num_columns() {
             return Err(Error::OutOfBounds);
         }
-        let row = self.highest_unfilled_row(column)?;
-        self.cells[(row, column)] = Some(self.cur_turn);
+        if self.cells[(0, column)] != Some(self.cur_turn) {
+            return Err(Error::CannotPop);
+        }
+        for row in 1..self.num_rows() {
+            self.cells[(row - 1, column)] = self.cells[(row, column)];
+        }
+        let top_row = self.num_rows() - 1;
+        self.cells[(top_row, column)] = None;
         Ok(())
     }
 
-    fn highest_unfilled_row(&self, column: usize) -> Result<usize, Error> {
-        self.cells
-            .column_iter(column)
-            .enumerate()
-            .find(|(_, cell)| cell.is_none())
-            .map(|(index, _)| index)
-            .ok_or(Error::ColumnFull)
+    fn place_chip_cells(&mut self, row: usize, column: usize) -> Result<(), Error> {
+        if self.placement != Placement::Free {
+            return Err(Error::PlaceNotAllowed);
+        }
+        match self.cells.get(row, column) {
+            None => Err(Error::OutOfBounds),
+            Some(Some(_)) => Err(Error::CellOccupied),
+            Some(None) => {
+                self.cells[(row, column)] = Some(self.cur_turn);
+                Ok(())
+            }
+        }
+    }
+
+    fn drop_chip_cells(&mut self, column: usize) -> Result<usize, Error> {
+        if self.placement != Placement::Gravity {
+            return Err(Error::DropNotAllowed);
+        }
+        let row = self.column_height(column)?;
+        if row == self.num_rows() {
+            return Err(Error::ColumnFull);
+        }
+        self.cells[(row, column)] = Some(self.cur_turn);
+        Ok(row)
     }
 
     fn create_empty_grid_rows(num_rows: usize, num_columns: usize) -> Vec<Vec<Cell>> {
@@ -277,8 +1363,255 @@ impl GameState {
     }
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            Error::OutOfBounds => "that column is out of bounds",
+            Error::ColumnFull => "that column is full",
+            Error::NotThatTeamsTurn => "it is not that team's turn",
+            Error::InvalidTeam => "that is not a valid team",
+            Error::GameOver => "the game is already over",
+            Error::NothingToUndo => "there are no moves to undo",
+            Error::NothingToRedo => "there are no moves to redo",
+            Error::UnsupportedBoard => "that board is not supported",
+            Error::NoTeams => "there must be at least one team",
+            Error::InvalidDimensions => "the board needs at least one row and one column",
+            Error::InvalidWinningLength => {
+                "the winning length must be at least one and fit on the board"
+            }
+            Error::WrongNumberOfPlayers => "there must be exactly one player per team",
+            Error::InvalidTeamName => "a team name must be a single line that is not blank",
+            Error::InconsistentState => "the board, moves and turn do not agree",
+            Error::PopOutNotAllowed => "chips can only be popped out in a PopOut game",
+            Error::CannotPop => "only your own chips can be popped out of the bottom row",
+            Error::UnsupportedMove => "that move cannot be written as a column",
+            Error::DropNotAllowed => "chips are placed on a cell rather than dropped in this game",
+            Error::PlaceNotAllowed => "chips can only be placed on a cell in a free placement game",
+            Error::CellOccupied => "that cell is already taken",
+            Error::IncompatibleRules => "those rules cannot be played together",
+            Error::InvalidGlyph => {
+                "a team's symbol must be visible and not used by another team or empty cells"
+            }
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for MoveStringError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveStringError::InvalidCharacter(index, c) => {
+                write!(f, "{:?} at position {} is not a column", c, index)
+            }
+            MoveStringError::InvalidMove(index, e) => {
+                write!(f, "the move at position {} is invalid because {}", index, e)
+            }
+            MoveStringError::InvalidConfig(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for MoveStringError {}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Direction::Vertical => "vertical",
+            Direction::Horizontal => "horizontal",
+            Direction::UpLeft => "up-left diagonal",
+            Direction::UpRight => "up-right diagonal",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Move::Drop(column) => write!(f, "column {:X}", column),
+            Move::Pop(column) => write!(f, "pop {:X}", column),
+            Move::Place(row, column) => write!(f, "row {:X} column {:X}", row, column),
+        }
+    }
+}
+
 impl fmt::Display for Team {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Team {}", self.0)
     }
 }
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+    use serde::de::{self, Deserialize, Deserializer};
+    use serde::ser::{Serialize, Serializer};
+
+    /// What a `GameState` is written as. Everything else is worked out again
+    /// when it is read back.
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[serde(rename = "GameState")]
+    struct GameStateData {
+        num_teams: usize,
+        winning_length: usize,
+        first_turn: Team,
+        cur_turn: Team,
+        /// The rows from the bottom up.
+        cells: Vec<Vec<Cell>>,
+        history: Vec<PlayedMove>,
+        undone: Vec<PlayedMove>,
+        team_info: Vec<TeamInfo>,
+        #[serde(default)]
+        pop_out: bool,
+        #[serde(default)]
+        topology: Topology,
+        #[serde(default)]
+        placement: Placement,
+        #[serde(default)]
+        win_rule: WinRule,
+        #[serde(default)]
+        scoring: ScoringMode,
+    }
+
+    impl Serialize for GameState {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            GameStateData {
+                num_teams: self.num_teams,
+                winning_length: self.winning_length,
+                first_turn: self.first_turn,
+                cur_turn: self.cur_turn,
+                cells: self.cells.as_rows(),
+                history: self.history.clone(),
+                undone: self.undone.clone(),
+                team_info: self.team_info.clone(),
+                pop_out: self.pop_out,
+                topology: self.topology,
+                placement: self.placement,
+                win_rule: self.win_rule,
+                scoring: self.scoring,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for GameState {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<GameState, D::Error> {
+            let data = GameStateData::deserialize(deserializer)?;
+            GameState::from_data(data).map_err(de::Error::custom)
+        }
+    }
+
+    impl GameState {
+        /// Rebuilds a game, checking that the cells could have been reached
+        /// by playing the history on top of chips that were already there.
+        fn from_data(data: GameStateData) -> Result<GameState, Error> {
+            if data.num_teams == 0 {
+                return Err(Error::NoTeams);
+            }
+            let num_rows = data.cells.len();
+            let num_columns = data.cells.first().map_or(0, Vec::len);
+            validate_board(num_rows, num_columns, data.winning_length)?;
+            if data.cells.iter().any(|row| row.len() != num_columns) {
+                return Err(Error::InvalidDimensions);
+            }
+            let mut cells = Array2D::from_rows(&data.cells);
+            let teams_valid = cells
+                .elements_row_major_iter()
+                .flatten()
+                .chain(data.history.iter().map(|played| &played.team))
+                .chain([data.first_turn, data.cur_turn].iter())
+                .all(|team| team.0 < data.num_teams);
+            if !teams_valid || data.team_info.len() != data.num_teams {
+                return Err(Error::InvalidTeam);
+            }
+
+            // Take the history back off the board, newest move first.
+            for played in data.history.iter().rev() {
+                if played.column >= num_columns || played.row >= num_rows {
+                    return Err(Error::InconsistentState);
+                }
+                match played.kind {
+                    MoveKind::Place => {
+                        if cells[(played.row, played.column)] != Some(played.team) {
+                            return Err(Error::InconsistentState);
+                        }
+                        cells[(played.row, played.column)] = None;
+                    }
+                    MoveKind::Drop => {
+                        let on_top = played.row + 1 == num_rows
+                            || cells[(played.row + 1, played.column)].is_none();
+                        if cells[(played.row, played.column)] != Some(played.team) || !on_top {
+                            return Err(Error::InconsistentState);
+                        }
+                        cells[(played.row, played.column)] = None;
+                    }
+                    MoveKind::Pop => {
+                        let top_row = num_rows - 1;
+                        if played.row != 0 || cells[(top_row, played.column)].is_some() {
+                            return Err(Error::InconsistentState);
+                        }
+                        unpop_cells(&mut cells, played.team, played.column);
+                    }
+                }
+            }
+            let config = GameConfig {
+                first_turn: data.first_turn,
+                num_teams: data.num_teams,
+                num_rows,
+                num_columns,
+                winning_length: data.winning_length,
+                pop_out: data.pop_out,
+                topology: data.topology,
+                placement: data.placement,
+                win_rule: data.win_rule,
+                scoring: data.scoring,
+            };
+            let mut game = GameState::from_starting_cells(&config, cells)?;
+            for played in &data.history {
+                game.play_move(played.team, played.to_move())
+                    .map_err(|_| Error::InconsistentState)?;
+            }
+            if game.cur_turn != data.cur_turn {
+                return Err(Error::InconsistentState);
+            }
+            let mut redone = game.clone();
+            for played in data.undone.iter().rev() {
+                redone.redo_move(*played)?;
+            }
+            game.undone = data.undone;
+            GameState::validate_team_info(&data.team_info)?;
+            game.team_info = data.team_info;
+            Ok(game)
+        }
+
+        /// Plays a move from the redo stack, checking it lands where it did.
+        fn redo_move(&mut self, played: PlayedMove) -> Result<(), Error> {
+            let landed = self
+                .play_move(played.team, played.to_move())
+                .map_err(|_| Error::InconsistentState)?;
+            let expected = match played.kind {
+                MoveKind::Drop => GameEvent::ChipDropped {
+                    team: played.team,
+                    row: played.row,
+                    column: played.column,
+                },
+                MoveKind::Pop if played.row == 0 => GameEvent::ChipPopped {
+                    team: played.team,
+                    column: played.column,
+                },
+                MoveKind::Pop => return Err(Error::InconsistentState),
+                MoveKind::Place => GameEvent::ChipPlaced {
+                    team: played.team,
+                    row: played.row,
+                    column: played.column,
+                },
+            };
+            if landed.first() != Some(&expected) {
+                return Err(Error::InconsistentState);
+            }
+            Ok(())
+        }
+    }
+}
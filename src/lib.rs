@@ -1,2 +1,10 @@
+pub mod ai;
+pub mod bitboard;
 pub mod cli;
 pub mod game;
+pub mod mcts;
+pub mod player;
+pub mod render;
+pub mod save;
+pub mod solver;
+pub mod tui;
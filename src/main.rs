@@ -1,6 +1,5 @@
-pub mod cli;
-mod game;
+use connect_four::tui;
 
 fn main() {
-    cli::run();
+    tui::run();
 }
@@ -0,0 +1,198 @@
+use crate::game::*;
+use crate::player::Player;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_ITERATIONS: usize = 10_000;
+pub const DEFAULT_EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+/// How long the search is allowed to run for each move.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Budget {
+    Iterations(usize),
+    Time(Duration),
+}
+
+/// A computer player using Monte Carlo tree search with the UCT selection
+/// rule. It only relies on the rules in `GameState`, so it works for any
+/// number of teams, board size and winning length.
+///
+/// The same seed always gives the same moves when the budget is a number of
+/// iterations.
+pub struct Mcts {
+    budget: Budget,
+    exploration: f64,
+    rng: Rng,
+}
+
+impl Mcts {
+    pub fn new(budget: Budget, seed: u64) -> Mcts {
+        Mcts::with_exploration(budget, seed, DEFAULT_EXPLORATION)
+    }
+
+    /// Higher `exploration` spends more time on moves that look worse so
+    /// far.
+    pub fn with_exploration(budget: Budget, seed: u64, exploration: f64) -> Mcts {
+        Mcts {
+            budget,
+            exploration,
+            rng: Rng(seed),
+        }
+    }
+
+    pub fn budget(&self) -> Budget {
+        self.budget
+    }
+
+    /// Returns the column of the most explored move for the team whose turn
+    /// it is, or `None` if there are no legal moves.
+    pub fn choose_column(&mut self, game: &GameState) -> Option<usize> {
+        self.best_move(game).map(Move::column)
+    }
+
+    /// Returns the most explored move for the team whose turn it is, or
+    /// `None` if there are no legal moves.
+    pub fn best_move(&mut self, game: &GameState) -> Option<Move> {
+        let moves = game.available_moves();
+        if moves.len() <= 1 {
+            return moves.first().cloned();
+        }
+        let mut tree = vec![Node::new(None, None, moves)];
+        let start = Instant::now();
+        let mut iterations = 0;
+        while !self.budget_spent(iterations, start) {
+            self.iterate(&mut tree, game);
+            iterations += 1;
+        }
+        tree[0]
+            .children
+            .iter()
+            .max_by_key(|&&child| tree[child].visits)
+            .and_then(|&child| tree[child].mv)
+    }
+
+    fn budget_spent(&self, iterations: usize, start: Instant) -> bool {
+        match self.budget {
+            // Always search at least once so there is a move to pick
+            Budget::Iterations(limit) => iterations >= limit.max(1),
+            Budget::Time(limit) => iterations > 0 && start.elapsed() >= limit,
+        }
+    }
+
+    /// Runs one round of selection, expansion, simulation and
+    /// backpropagation.
+    fn iterate(&mut self, tree: &mut Vec<Node>, root: &GameState) {
+        let mut game = root.clone();
+        let mut index = 0;
+        // Selection
+        while tree[index].untried.is_empty() && !tree[index].children.is_empty() {
+            index = self.select_child(tree, index);
+            let mv = tree[index].mv.unwrap();
+            let team = game.cur_turn();
+            game.play_move(team, mv).unwrap();
+        }
+        // Expansion
+        if !tree[index].untried.is_empty() {
+            let untried = &mut tree[index].untried;
+            let mv = untried.swap_remove(self.rng.below(untried.len()));
+            let team = game.cur_turn();
+            game.play_move(team, mv).unwrap();
+            let child = Node::new(Some(index), Some((team, mv)), game.available_moves());
+            tree.push(child);
+            let child_index = tree.len() - 1;
+            tree[index].children.push(child_index);
+            index = child_index;
+        }
+        // Simulation
+        while !game.game_over() {
+            let moves = game.available_moves();
+            let mv = moves[self.rng.below(moves.len())];
+            let team = game.cur_turn();
+            game.play_move(team, mv).unwrap();
+        }
+        // Backpropagation
+        let outcome = game.outcome();
+        let num_teams = game.num_teams();
+        let mut current = Some(index);
+        while let Some(i) = current {
+            let node = &mut tree[i];
+            node.visits += 1;
+            if let Some(team) = node.team {
+                node.reward += reward(outcome, team, num_teams);
+            }
+            current = node.parent;
+        }
+    }
+
+    fn select_child(&self, tree: &[Node], index: usize) -> usize {
+        let parent_visits = (tree[index].visits as f64).ln();
+        let uct = |child: usize| {
+            let node = &tree[child];
+            let visits = node.visits as f64;
+            node.reward / visits + self.exploration * (parent_visits / visits).sqrt()
+        };
+        *tree[index]
+            .children
+            .iter()
+            .max_by(|&&a, &&b| uct(a).partial_cmp(&uct(b)).unwrap())
+            .unwrap()
+    }
+}
+
+impl Player for Mcts {
+    fn choose_move(&mut self, game: &GameState) -> Move {
+        self.best_move(game)
+            .expect("asked for a move in a game that is over")
+    }
+}
+
+/// What a finished game is worth to `team`. Draws are shared equally.
+fn reward(outcome: GameOutcome, team: Team, num_teams: usize) -> f64 {
+    match outcome {
+        GameOutcome::Won(winner) if winner == team => 1.0,
+        GameOutcome::Draw => 1.0 / num_teams as f64,
+        _ => 0.0,
+    }
+}
+
+struct Node {
+    parent: Option<usize>,
+    /// The team that moved into this node, which its reward is counted for.
+    team: Option<Team>,
+    mv: Option<Move>,
+    children: Vec<usize>,
+    untried: Vec<Move>,
+    visits: u32,
+    reward: f64,
+}
+
+impl Node {
+    fn new(parent: Option<usize>, played: Option<(Team, Move)>, untried: Vec<Move>) -> Node {
+        Node {
+            parent,
+            team: played.map(|(team, _)| team),
+            mv: played.map(|(_, mv)| mv),
+            children: Vec::new(),
+            untried,
+            visits: 0,
+            reward: 0.0,
+        }
+    }
+}
+
+/// SplitMix64, which is plenty for picking random moves.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..limit`.
+    fn below(&mut self, limit: usize) -> usize {
+        (self.next() % limit as u64) as usize
+    }
+}
@@ -0,0 +1,161 @@
+use crate::game::*;
+
+/// Anything that can take a seat in a `Match`: a person at the keyboard, a
+/// computer opponent or a connection to a remote player.
+pub trait Player {
+    /// Picks a move for the team whose turn it is. Only called when it is
+    /// this player's turn and the game is not over.
+    fn choose_move(&mut self, game: &GameState) -> Move;
+
+    /// Called after another team makes a move.
+    fn opponent_moved(&mut self, _game: &GameState, _team: Team, _mv: Move) {}
+
+    /// Called once the game has been won or drawn.
+    fn game_ended(&mut self, _game: &GameState) {}
+
+    /// Called after any team's move is taken back, including this player's.
+    fn move_undone(&mut self, _game: &GameState, _team: Team, _mv: Move) {}
+
+    /// Called after the whole game is swapped for another, so anything kept
+    /// about the old one should be dropped.
+    fn game_replaced(&mut self, _game: &GameState) {}
+}
+
+/// Something that hears about everything that happens in a `Match`, such
+/// as a logger, a sound effect or a statistics collector.
+pub trait Observer {
+    fn on_event(&mut self, game: &GameState, event: &GameEvent);
+}
+
+impl<F> Observer for F
+where
+    F: FnMut(&GameState, &GameEvent),
+{
+    fn on_event(&mut self, game: &GameState, event: &GameEvent) {
+        self(game, event)
+    }
+}
+
+/// Owns a game and one player per team, asking each player for a move when
+/// it is their turn and letting everyone else know what happened.
+pub struct Match {
+    game: GameState,
+    players: Vec<Box<dyn Player>>,
+    observers: Vec<Box<dyn Observer>>,
+}
+
+impl Match {
+    /// `players[i]` plays `Team::new(i)`.
+    pub fn new(game: GameState, players: Vec<Box<dyn Player>>) -> Result<Match, Error> {
+        if players.len() != game.num_teams() {
+            return Err(Error::WrongNumberOfPlayers);
+        }
+        Ok(Match {
+            game,
+            players,
+            observers: Vec::new(),
+        })
+    }
+
+    /// Sends every event from now on to `observer`, after the game has
+    /// been updated.
+    pub fn subscribe(&mut self, observer: Box<dyn Observer>) {
+        self.observers.push(observer);
+    }
+
+    pub fn game(&self) -> &GameState {
+        &self.game
+    }
+
+    pub fn into_game(self) -> GameState {
+        self.game
+    }
+
+    /// Swaps in a different game with the same number of teams, such as one
+    /// loaded from a file.
+    pub fn set_game(&mut self, game: GameState) -> Result<(), Error> {
+        if self.players.len() != game.num_teams() {
+            return Err(Error::WrongNumberOfPlayers);
+        }
+        self.game = game;
+        for player in self.players.iter_mut() {
+            player.game_replaced(&self.game);
+        }
+        Ok(())
+    }
+
+    /// Plays turns until the game is over.
+    pub fn play(&mut self) -> Result<GameOutcome, Error> {
+        while !self.game.game_over() {
+            self.play_turn()?;
+        }
+        Ok(self.game.outcome())
+    }
+
+    /// Asks the team whose turn it is for a move and plays it, returning
+    /// the move.
+    pub fn play_turn(&mut self) -> Result<Move, Error> {
+        if self.game.game_over() {
+            return Err(Error::GameOver);
+        }
+        let team = self.game.cur_turn();
+        let mv = self.players[team.index()].choose_move(&self.game);
+        self.play_move(mv)?;
+        Ok(mv)
+    }
+
+    /// Drops a chip for the team whose turn it is without asking its player,
+    /// for front ends that read moves themselves.
+    pub fn drop_chip(&mut self, column: usize) -> Result<(), Error> {
+        self.play_move(Move::Drop(column))
+    }
+
+    /// Makes a move for the team whose turn it is without asking its player.
+    pub fn play_move(&mut self, mv: Move) -> Result<(), Error> {
+        let team = self.game.cur_turn();
+        let events = self.game.play_move(team, mv)?;
+        self.notify_observers(&events);
+        self.notify_move(team, mv);
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> Result<(), Error> {
+        let played = *self.game.history().last().ok_or(Error::NothingToUndo)?;
+        let events = self.game.undo()?;
+        self.notify_observers(&events);
+        for player in self.players.iter_mut() {
+            player.move_undone(&self.game, played.team, played.to_move());
+        }
+        Ok(())
+    }
+
+    pub fn redo(&mut self) -> Result<(), Error> {
+        let events = self.game.redo()?;
+        self.notify_observers(&events);
+        let played = *self.game.history().last().unwrap();
+        self.notify_move(played.team, played.to_move());
+        Ok(())
+    }
+
+    fn notify_observers(&mut self, events: &[GameEvent]) {
+        for observer in self.observers.iter_mut() {
+            for event in events {
+                observer.on_event(&self.game, event);
+            }
+        }
+    }
+
+    fn notify_move(&mut self, team: Team, mv: Move) {
+        let game = &self.game;
+        for (index, player) in self.players.iter_mut().enumerate() {
+            if index != team.index() {
+                player.opponent_moved(game, team, mv);
+            }
+        }
+        if game.game_over() {
+            for player in self.players.iter_mut() {
+                player.game_ended(game);
+            }
+        }
+    }
+}
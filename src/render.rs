@@ -0,0 +1,201 @@
+use crate::game::{GameState, MoveKind, Placement, Team, Topology, DEFAULT_EMPTY_CHAR};
+use std::env;
+
+const ESC: char = 27 as char;
+
+const DISC_GLYPH: char = '●';
+const LAST_MOVE_GLYPH: char = '◉';
+const EMPTY_GLYPH: char = '·';
+
+/// 256-color codes for the team discs, picked to be far apart in hue.
+const STANDARD_COLORS: [u8; 16] = [
+    196, 226, 33, 46, 201, 51, 208, 93, 218, 118, 130, 30, 255, 244, 19, 100,
+];
+
+/// Foreground and background 256-color pairs. The team's glyph is printed
+/// on the background so teams can be told apart without seeing color.
+const HIGH_CONTRAST_COLORS: [(u8, u8); 8] = [
+    (16, 226),
+    (231, 21),
+    (16, 231),
+    (231, 160),
+    (16, 46),
+    (231, 90),
+    (16, 208),
+    (16, 51),
+];
+
+/// The set of colors used by `Renderer::Color`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Palette {
+    /// A colored disc for each team.
+    Standard,
+    /// Each team's glyph in bold on a bright background.
+    HighContrast,
+}
+
+/// Draws a game board as text for the terminal.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Renderer {
+    /// One character per cell, with no colors.
+    Plain,
+    /// ANSI colors, with the last move marked and a legend of teams.
+    Color(Palette),
+}
+
+impl Renderer {
+    /// A color renderer with the given palette, or the plain renderer if the
+    /// `NO_COLOR` environment variable is set.
+    pub fn for_terminal(palette: Palette) -> Renderer {
+        match env::var_os("NO_COLOR") {
+            Some(ref value) if !value.is_empty() => Renderer::Plain,
+            _ => Renderer::Color(palette),
+        }
+    }
+
+    /// The board with a column header, top row first. Highlighted cells are
+    /// shown in reverse video. On a cylindrical board the line under the
+    /// header ends in arrows to show that the edges join, and in a free
+    /// placement game each row ends with its number.
+    pub fn render(self, game: &GameState, highlighted: &[(usize, usize)]) -> String {
+        let width = self.column_width(game);
+        let label_width = self.row_label_width(game);
+        let last_move = game
+            .history()
+            .last()
+            .filter(|m| m.kind != MoveKind::Pop)
+            .map(|m| (m.row, m.column));
+        let grid_s = (0..game.num_rows())
+            .rev()
+            .map(|row| {
+                let cells = (0..game.num_columns())
+                    .map(|column| {
+                        let cell = match game.cell(row, column) {
+                            Some(team) => {
+                                let is_last_move = last_move == Some((row, column));
+                                self.chip_string(game, team, is_last_move)
+                            }
+                            None => self.empty_string(width),
+                        };
+                        if highlighted.contains(&(row, column)) {
+                            format!("{}[7m{}{}[0m", ESC, cell, ESC)
+                        } else {
+                            cell
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                match label_width {
+                    0 => cells,
+                    _ => format!("{} {:>width$X}", cells, row, width = label_width - 1),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let header: String = (0..game.num_columns())
+            .map(|i| format!("{:>width$X}", i, width = width))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let line_width = game.num_columns() * (width + 1) - 1;
+        let lines = match game.topology() {
+            Topology::Flat => "-".repeat(line_width),
+            Topology::Cylinder => format!("<{}>", "-".repeat(line_width.saturating_sub(2))),
+        };
+        let board = format!("{}\n{}\n{}", header, lines, grid_s);
+        match self {
+            Renderer::Plain => board,
+            Renderer::Color(_) => format!("{}\n\n{}", board, self.legend(game)),
+        }
+    }
+
+    /// How many characters wide each column of the board is: wide enough
+    /// for the longest team glyph and the largest column number.
+    pub fn column_width(self, game: &GameState) -> usize {
+        let header_width = format!("{:X}", game.num_columns().saturating_sub(1)).len();
+        game.cell_width().max(header_width)
+    }
+
+    /// How many characters the row numbers after each row take, including
+    /// the space before them. Only free placement games have row numbers.
+    pub fn row_label_width(self, game: &GameState) -> usize {
+        match game.placement() {
+            Placement::Gravity => 0,
+            Placement::Free => format!("{:X}", game.num_rows() - 1).len() + 1,
+        }
+    }
+
+    /// How a chip of the given team is drawn on the board, padded to the
+    /// column width.
+    pub fn team_glyph(self, game: &GameState, team: Team) -> String {
+        self.chip_string(game, team, false)
+    }
+
+    /// Each team's glyph followed by its name.
+    fn legend(self, game: &GameState) -> String {
+        (0..game.num_teams())
+            .map(Team::new)
+            .map(|team| format!("{} {}", self.team_glyph(game, team), game.team_name(team)))
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+
+    fn empty_string(self, width: usize) -> String {
+        match self {
+            Renderer::Plain => DEFAULT_EMPTY_CHAR.to_string().repeat(width),
+            Renderer::Color(_) => {
+                let padding = " ".repeat(width - 1);
+                format!("{}{}[2m{}{}[0m", padding, ESC, EMPTY_GLYPH, ESC)
+            }
+        }
+    }
+
+    fn chip_string(self, game: &GameState, team: Team, is_last_move: bool) -> String {
+        let info = game.team_info(team);
+        let width = self.column_width(game);
+        let padded_glyph = format!("{:>width$}", info.glyph, width = width);
+        let palette = match self {
+            Renderer::Plain => return padded_glyph,
+            Renderer::Color(palette) => palette,
+        };
+        let padding = " ".repeat(width - 1);
+        match palette {
+            // Discs stop being told apart once the colors repeat, so larger
+            // games show each team's glyph in its color instead.
+            Palette::Standard if game.num_teams() > STANDARD_COLORS.len() => {
+                let color = info
+                    .color
+                    .unwrap_or(STANDARD_COLORS[team.index() % STANDARD_COLORS.len()]);
+                let underline = if is_last_move { ";4" } else { "" };
+                format!(
+                    "{}[1{};38;5;{}m{}{}[0m",
+                    ESC, underline, color, padded_glyph, ESC
+                )
+            }
+            Palette::Standard => {
+                let color = info
+                    .color
+                    .unwrap_or(STANDARD_COLORS[team.index() % STANDARD_COLORS.len()]);
+                let glyph = if is_last_move {
+                    LAST_MOVE_GLYPH
+                } else {
+                    DISC_GLYPH
+                };
+                format!("{}{}[38;5;{}m{}{}[0m", padding, ESC, color, glyph, ESC)
+            }
+            Palette::HighContrast => {
+                let (fg, bg) = HIGH_CONTRAST_COLORS[team.index() % HIGH_CONTRAST_COLORS.len()];
+                let underline = if is_last_move { ";4" } else { "" };
+                format!(
+                    "{}[1{};38;5;{};48;5;{}m{}{}[0m",
+                    ESC, underline, fg, bg, padded_glyph, ESC
+                )
+            }
+        }
+    }
+}
+
+impl Default for Renderer {
+    fn default() -> Renderer {
+        Renderer::Color(Palette::Standard)
+    }
+}
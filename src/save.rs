@@ -0,0 +1,347 @@
+use crate::game::*;
+use array2d::Array2D;
+use std::path::Path;
+use std::{error, fmt, fs, io};
+
+const HEADER: &str = "# connect-four save file";
+
+/// Something that went wrong while saving or loading a game.
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    /// A line that is not a known `key value` pair, by line number.
+    InvalidLine(usize),
+    /// A value that could not be parsed, by line number.
+    InvalidValue(usize),
+    MissingField(&'static str),
+    /// A saved move that could not be replayed, by its index in the moves.
+    InvalidMove(usize, Error),
+    /// The saved current turn does not match the one after replaying.
+    TurnMismatch,
+    Game(Error),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "{}", e),
+            SaveError::InvalidLine(line) => write!(f, "line {} is not understood", line),
+            SaveError::InvalidValue(line) => write!(f, "line {} has an invalid value", line),
+            SaveError::MissingField(field) => write!(f, "the {} field is missing", field),
+            SaveError::InvalidMove(index, e) => {
+                write!(f, "move {} could not be played because {}", index + 1, e)
+            }
+            SaveError::TurnMismatch => write!(f, "the current turn does not match the moves"),
+            SaveError::Game(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> SaveError {
+        SaveError::Io(e)
+    }
+}
+
+impl From<Error> for SaveError {
+    fn from(e: Error) -> SaveError {
+        SaveError::Game(e)
+    }
+}
+
+pub fn save<P: AsRef<Path>>(game: &GameState, path: P) -> Result<(), SaveError> {
+    fs::write(path, to_save_string(game))?;
+    Ok(())
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> Result<GameState, SaveError> {
+    from_save_string(&fs::read_to_string(path)?)
+}
+
+/// Writes the game settings and the columns played so far, one setting per
+/// line. Teams that are not shown the default way get a
+/// `team <index> <glyph> <color or -> <name>` line, PopOut games get a
+/// `pop-out yes` line and pops are written as the column prefixed with `p`.
+/// Cylindrical boards get a `topology cylinder` line, and free placement
+/// games get a `placement free` line with moves written as `<row>,<column>`.
+/// Win rules other than the usual one get a `win-rule` line, and games
+/// scored by counting lines get a `scoring count-lines` line. Games that
+/// began with chips already on the board get a `start <row> <cells>` line
+/// for each row holding one, with each cell a team index or `-`.
+pub fn to_save_string(game: &GameState) -> String {
+    let mut start = game.clone();
+    while start.undo().is_ok() {}
+    let start_lines: String = if game.moves_played() == game.history().len() {
+        String::new()
+    } else {
+        (0..game.num_rows())
+            .filter(|&row| (0..game.num_columns()).any(|column| start.cell(row, column).is_some()))
+            .map(|row| {
+                let cells = (0..game.num_columns())
+                    .map(|column| {
+                        start
+                            .cell(row, column)
+                            .map_or_else(|| "-".to_string(), |team| team.index().to_string())
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                format!("start {} {}\n", row, cells)
+            })
+            .collect()
+    };
+    let moves = game
+        .history()
+        .iter()
+        .map(|played| match played.kind {
+            MoveKind::Drop => played.column.to_string(),
+            MoveKind::Pop => format!("p{}", played.column),
+            MoveKind::Place => format!("{},{}", played.row, played.column),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let pop_out_line = if game.pop_out() { "pop-out yes\n" } else { "" };
+    let topology_line = match game.topology() {
+        Topology::Flat => "",
+        Topology::Cylinder => "topology cylinder\n",
+    };
+    let placement_line = match game.placement() {
+        Placement::Gravity => "",
+        Placement::Free => "placement free\n",
+    };
+    let win_rule_line = match game.win_rule() {
+        WinRule::AtLeast => "",
+        WinRule::Exactly => "win-rule exactly\n",
+        WinRule::OverlineLoses => "win-rule overline-loses\n",
+    };
+    let scoring_line = match game.scoring() {
+        ScoringMode::FirstLine => "",
+        ScoringMode::CountLines => "scoring count-lines\n",
+    };
+    let team_lines: String = (0..game.num_teams())
+        .map(Team::new)
+        .filter(|&team| *game.team_info(team) != TeamInfo::default_for(team))
+        .map(|team| {
+            let info = game.team_info(team);
+            let color = info
+                .color
+                .map_or_else(|| "-".to_string(), |color| color.to_string());
+            format!(
+                "team {} {} {} {}\n",
+                team.index(),
+                info.glyph,
+                color,
+                info.name
+            )
+        })
+        .collect();
+    format!(
+        "{}\nteams {}\nrows {}\ncolumns {}\nwinning-length {}\n{}{}{}{}{}first-turn {}\ncurrent-turn {}\n{}{}moves {}\n",
+        HEADER,
+        game.num_teams(),
+        game.num_rows(),
+        game.num_columns(),
+        game.winning_length(),
+        pop_out_line,
+        topology_line,
+        placement_line,
+        win_rule_line,
+        scoring_line,
+        game.first_turn().index(),
+        game.cur_turn().index(),
+        team_lines,
+        start_lines,
+        moves,
+    )
+}
+
+/// Reads a game written by `to_save_string`, replaying every move so that
+/// the result follows the normal rules.
+pub fn from_save_string(contents: &str) -> Result<GameState, SaveError> {
+    let mut fields = SaveFields::default();
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let key = words.next().unwrap();
+        let mut value = || -> Result<usize, SaveError> {
+            let value = words.next().ok_or(SaveError::InvalidValue(line_number))?;
+            value
+                .parse()
+                .map_err(|_| SaveError::InvalidValue(line_number))
+        };
+        match key {
+            "teams" => fields.num_teams = Some(value()?),
+            "rows" => fields.num_rows = Some(value()?),
+            "columns" => fields.num_columns = Some(value()?),
+            "winning-length" => fields.winning_length = Some(value()?),
+            "first-turn" => fields.first_turn = Some(value()?),
+            "current-turn" => fields.cur_turn = Some(value()?),
+            "pop-out" => {
+                fields.pop_out = match words.next() {
+                    Some("yes") => true,
+                    Some("no") => false,
+                    _ => return Err(SaveError::InvalidValue(line_number)),
+                }
+            }
+            "topology" => {
+                fields.topology = match words.next() {
+                    Some("flat") => Topology::Flat,
+                    Some("cylinder") => Topology::Cylinder,
+                    _ => return Err(SaveError::InvalidValue(line_number)),
+                }
+            }
+            "placement" => {
+                fields.placement = match words.next() {
+                    Some("gravity") => Placement::Gravity,
+                    Some("free") => Placement::Free,
+                    _ => return Err(SaveError::InvalidValue(line_number)),
+                }
+            }
+            "win-rule" => {
+                fields.win_rule = match words.next() {
+                    Some("at-least") => WinRule::AtLeast,
+                    Some("exactly") => WinRule::Exactly,
+                    Some("overline-loses") => WinRule::OverlineLoses,
+                    _ => return Err(SaveError::InvalidValue(line_number)),
+                }
+            }
+            "scoring" => {
+                fields.scoring = match words.next() {
+                    Some("first-line") => ScoringMode::FirstLine,
+                    Some("count-lines") => ScoringMode::CountLines,
+                    _ => return Err(SaveError::InvalidValue(line_number)),
+                }
+            }
+            "team" => {
+                let team = value()?;
+                let info = parse_team_info(line).ok_or(SaveError::InvalidValue(line_number))?;
+                fields.team_info.push((team, info));
+            }
+            "start" => {
+                let row = value()?;
+                let cells = words
+                    .map(|word| match word {
+                        "-" => Some(None),
+                        team => team.parse().ok().map(|team| Some(Team::new(team))),
+                    })
+                    .collect::<Option<_>>()
+                    .ok_or(SaveError::InvalidValue(line_number))?;
+                fields.start.push((line_number, row, cells));
+            }
+            "moves" => {
+                fields.moves = Some(
+                    words
+                        .map(parse_move)
+                        .collect::<Option<_>>()
+                        .ok_or(SaveError::InvalidValue(line_number))?,
+                )
+            }
+            _ => return Err(SaveError::InvalidLine(line_number)),
+        }
+    }
+    fields.into_game()
+}
+
+/// Reads a column to drop in, a column prefixed with `p` to pop from or a
+/// `<row>,<column>` cell to place on.
+fn parse_move(word: &str) -> Option<Move> {
+    if let Some(column) = word.strip_prefix('p') {
+        return column.parse().ok().map(Move::Pop);
+    }
+    match word.split_once(',') {
+        Some((row, column)) => Some(Move::Place(row.parse().ok()?, column.parse().ok()?)),
+        None => word.parse().ok().map(Move::Drop),
+    }
+}
+
+/// Reads the glyph, color and name from a `team` line.
+fn parse_team_info(line: &str) -> Option<TeamInfo> {
+    let mut parts = line.splitn(5, ' ').skip(2);
+    let glyph = parts.next()?;
+    let color = parts.next()?;
+    let name = parts.next()?;
+    Some(TeamInfo {
+        name: name.to_string(),
+        glyph: glyph.to_string(),
+        color: match color {
+            "-" => None,
+            color => Some(color.parse().ok()?),
+        },
+    })
+}
+
+#[derive(Default)]
+struct SaveFields {
+    num_teams: Option<usize>,
+    num_rows: Option<usize>,
+    num_columns: Option<usize>,
+    winning_length: Option<usize>,
+    first_turn: Option<usize>,
+    cur_turn: Option<usize>,
+    pop_out: bool,
+    topology: Topology,
+    placement: Placement,
+    win_rule: WinRule,
+    scoring: ScoringMode,
+    /// The rows of chips the game began with, by line number.
+    start: Vec<(usize, usize, Vec<Option<Team>>)>,
+    moves: Option<Vec<Move>>,
+    team_info: Vec<(usize, TeamInfo)>,
+}
+
+impl SaveFields {
+    fn into_game(self) -> Result<GameState, SaveError> {
+        let first_turn = self
+            .first_turn
+            .ok_or(SaveError::MissingField("first-turn"))?;
+        let config = GameConfig {
+            first_turn: Team::new(first_turn),
+            num_teams: self.num_teams.ok_or(SaveError::MissingField("teams"))?,
+            num_rows: self.num_rows.ok_or(SaveError::MissingField("rows"))?,
+            num_columns: self.num_columns.ok_or(SaveError::MissingField("columns"))?,
+            winning_length: self
+                .winning_length
+                .ok_or(SaveError::MissingField("winning-length"))?,
+            pop_out: self.pop_out,
+            topology: self.topology,
+            placement: self.placement,
+            win_rule: self.win_rule,
+            scoring: self.scoring,
+        };
+        let mut game = if self.start.is_empty() {
+            GameState::from_config(&config)?
+        } else {
+            let mut cells = Array2D::filled_with(None, config.num_rows, config.num_columns);
+            for (line_number, row, row_cells) in self.start {
+                if row >= config.num_rows || row_cells.len() != config.num_columns {
+                    return Err(SaveError::InvalidValue(line_number));
+                }
+                for (column, cell) in row_cells.into_iter().enumerate() {
+                    cells[(row, column)] = cell;
+                }
+            }
+            GameState::from_starting_cells(&config, cells)?
+        };
+        for (team, info) in self.team_info {
+            game.set_team_info(Team::new(team), info)?;
+        }
+        let moves = self.moves.ok_or(SaveError::MissingField("moves"))?;
+        for (index, &mv) in moves.iter().enumerate() {
+            let team = game.cur_turn();
+            game.play_move(team, mv)
+                .map_err(|e| SaveError::InvalidMove(index, e))?;
+        }
+        let cur_turn = self
+            .cur_turn
+            .ok_or(SaveError::MissingField("current-turn"))?;
+        if game.cur_turn() != Team::new(cur_turn) {
+            return Err(SaveError::TurnMismatch);
+        }
+        Ok(game)
+    }
+}
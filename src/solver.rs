@@ -0,0 +1,378 @@
+use crate::bitboard::BitBoard;
+use crate::game::*;
+use std::fmt;
+
+const WIDTH: usize = DEFAULT_NUM_COLUMNS;
+const HEIGHT: usize = DEFAULT_NUM_ROWS;
+const NUM_CELLS: i32 = (WIDTH * HEIGHT) as i32;
+const MIN_SCORE: i32 = -NUM_CELLS / 2 + 3;
+
+/// Roughly 9 MB: a `u64` key and an `i8` value per entry.
+const TABLE_SIZE: usize = (1 << 20) + 7;
+
+const BOTTOM_MASK: u64 = bottom_mask();
+const BOARD_MASK: u64 = BOTTOM_MASK * ((1 << HEIGHT) - 1);
+
+/// The game-theoretic value of a position for the team whose turn it is.
+/// The number is how many chips will be dropped, by both teams, until the
+/// game ends when everyone plays perfectly.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Score {
+    Win(usize),
+    Loss(usize),
+    Draw,
+}
+
+impl Score {
+    /// Converts a score in the solver's internal scale, where a win with
+    /// your `n`th to last chip is worth `n`, at a position with `num_moves`
+    /// chips played.
+    fn from_value(value: i32, num_moves: usize) -> Score {
+        if value == 0 {
+            return Score::Draw;
+        }
+        // The winning chip is chip number `last_move`, counting from one,
+        // and it belongs to the team to move exactly when it is a win
+        let winner_parity = if value > 0 { 1 } else { 0 };
+        let total = (NUM_CELLS + 2 - 2 * value.abs()) as usize;
+        let last_move = if total % 2 == (num_moves + winner_parity) % 2 {
+            total
+        } else {
+            total - 1
+        };
+        let distance = last_move - num_moves;
+        if value > 0 {
+            Score::Win(distance)
+        } else {
+            Score::Loss(distance)
+        }
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Score::Win(moves) => write!(f, "win in {}", moves),
+            Score::Loss(moves) => write!(f, "loss in {}", moves),
+            Score::Draw => write!(f, "draw"),
+        }
+    }
+}
+
+/// Solves the position exactly. Only two-team 7x6 games with a winning
+/// length of four are supported.
+pub fn solve(game: &GameState) -> Result<Score, Error> {
+    Solver::new().solve(game)
+}
+
+/// The columns that lead to the best score for the team whose turn it is.
+pub fn best_moves(game: &GameState) -> Result<Vec<usize>, Error> {
+    Solver::new().best_moves(game)
+}
+
+/// Solves positions with a negamax search using alpha-beta pruning, a
+/// transposition table and move ordering. Keeping a `Solver` around reuses
+/// its table between positions.
+pub struct Solver {
+    keys: Vec<u64>,
+    values: Vec<i8>,
+}
+
+impl Default for Solver {
+    fn default() -> Solver {
+        Solver::new()
+    }
+}
+
+impl Solver {
+    pub fn new() -> Solver {
+        Solver {
+            keys: vec![0; TABLE_SIZE],
+            values: vec![0; TABLE_SIZE],
+        }
+    }
+
+    pub fn solve(&mut self, game: &GameState) -> Result<Score, Error> {
+        let position = Position::from_game_state(game)?;
+        if game.game_over() {
+            // Whoever won made the last move, so it was not the team to move
+            return Ok(match game.outcome() {
+                GameOutcome::Won(_) => Score::Loss(0),
+                _ => Score::Draw,
+            });
+        }
+        let value = self.solve_position(&position);
+        Ok(Score::from_value(value, position.num_moves))
+    }
+
+    /// The score of every column for the team whose turn it is, or `None`
+    /// for columns that cannot be played.
+    pub fn analyze(&mut self, game: &GameState) -> Result<Vec<Option<Score>>, Error> {
+        let num_moves = Position::from_game_state(game)?.num_moves;
+        Ok(self
+            .analyze_values(game)?
+            .into_iter()
+            .map(|value| value.map(|value| Score::from_value(value, num_moves)))
+            .collect())
+    }
+
+    pub fn best_moves(&mut self, game: &GameState) -> Result<Vec<usize>, Error> {
+        let values = self.analyze_values(game)?;
+        let best = values.iter().flatten().max();
+        Ok((0..WIDTH)
+            .filter(|&column| best.is_some() && values[column].as_ref() == best)
+            .collect())
+    }
+
+    fn analyze_values(&mut self, game: &GameState) -> Result<Vec<Option<i32>>, Error> {
+        let position = Position::from_game_state(game)?;
+        if game.game_over() {
+            return Ok(vec![None; WIDTH]);
+        }
+        Ok((0..WIDTH)
+            .map(|column| {
+                if !position.can_play(column) {
+                    None
+                } else if position.is_winning_move(column) {
+                    Some((NUM_CELLS + 1 - position.num_moves as i32) / 2)
+                } else {
+                    let mut child = position;
+                    child.play_column(column);
+                    Some(-self.solve_position(&child))
+                }
+            })
+            .collect())
+    }
+
+    fn solve_position(&mut self, position: &Position) -> i32 {
+        if position.can_win_next() {
+            return (NUM_CELLS + 1 - position.num_moves as i32) / 2;
+        }
+        let mut min = -(NUM_CELLS - position.num_moves as i32) / 2;
+        let mut max = (NUM_CELLS + 1 - position.num_moves as i32) / 2;
+        // Narrow the window with null window searches, which prune the most
+        while min < max {
+            let mut med = min + (max - min) / 2;
+            if med <= 0 && min / 2 < med {
+                med = min / 2;
+            } else if med >= 0 && max / 2 > med {
+                med = max / 2;
+            }
+            let value = self.negamax(position, med, med + 1);
+            if value <= med {
+                max = value;
+            } else {
+                min = value;
+            }
+        }
+        min
+    }
+
+    /// Assumes the team to move cannot win immediately.
+    fn negamax(&mut self, position: &Position, mut alpha: i32, mut beta: i32) -> i32 {
+        let possible = position.possible_non_losing_moves();
+        if possible == 0 {
+            return -(NUM_CELLS - position.num_moves as i32) / 2;
+        }
+        if position.num_moves as i32 >= NUM_CELLS - 2 {
+            return 0;
+        }
+        let min = -(NUM_CELLS - 2 - position.num_moves as i32) / 2;
+        if alpha < min {
+            alpha = min;
+            if alpha >= beta {
+                return alpha;
+            }
+        }
+        let key = position.key();
+        let index = (key % TABLE_SIZE as u64) as usize;
+        let max = if self.keys[index] == key {
+            self.values[index] as i32 + MIN_SCORE - 1
+        } else {
+            (NUM_CELLS - 1 - position.num_moves as i32) / 2
+        };
+        if beta > max {
+            beta = max;
+            if alpha >= beta {
+                return beta;
+            }
+        }
+        for chip in position.ordered_moves(possible) {
+            let mut child = *position;
+            child.play(chip);
+            let value = -self.negamax(&child, -beta, -alpha);
+            if value >= beta {
+                return value;
+            }
+            if value > alpha {
+                alpha = value;
+            }
+        }
+        // Only an upper bound is known, since no move reached alpha
+        self.keys[index] = key;
+        self.values[index] = (alpha - MIN_SCORE + 1) as i8;
+        alpha
+    }
+}
+
+/// The standard board, stored as the chips of the team to move and all
+/// occupied cells, using the same layout as `BitBoard`.
+#[derive(Copy, Clone)]
+struct Position {
+    current: u64,
+    occupied: u64,
+    num_moves: usize,
+}
+
+impl Position {
+    fn from_game_state(game: &GameState) -> Result<Position, Error> {
+        if game.num_rows() != HEIGHT
+            || game.num_columns() != WIDTH
+            || game.winning_length() != DEFAULT_WINNING_LENGTH
+        {
+            return Err(Error::UnsupportedBoard);
+        }
+        let board = BitBoard::from_game_state(game)?;
+        let (current, occupied) = board.current_and_occupied();
+        // The standard board takes 49 bits
+        Ok(Position {
+            current: current as u64,
+            occupied: occupied as u64,
+            num_moves: board.num_moves(),
+        })
+    }
+
+    /// Unique for every position. Adding the bottom row keeps the empty
+    /// board's key from matching an unused table entry.
+    fn key(&self) -> u64 {
+        self.current + self.occupied + BOTTOM_MASK
+    }
+
+    fn can_play(&self, column: usize) -> bool {
+        self.occupied & top_mask(column) == 0
+    }
+
+    fn play(&mut self, chip: u64) {
+        self.current ^= self.occupied;
+        self.occupied |= chip;
+        self.num_moves += 1;
+    }
+
+    fn play_column(&mut self, column: usize) {
+        self.play((self.occupied + bottom_mask_column(column)) & column_mask(column));
+    }
+
+    fn is_winning_move(&self, column: usize) -> bool {
+        self.winning_cells() & self.possible() & column_mask(column) != 0
+    }
+
+    fn can_win_next(&self) -> bool {
+        self.winning_cells() & self.possible() != 0
+    }
+
+    /// The cells a chip could be dropped into right now.
+    fn possible(&self) -> u64 {
+        (self.occupied + BOTTOM_MASK) & BOARD_MASK
+    }
+
+    /// Moves that do not let the opponent win on their next turn. If the
+    /// opponent has two immediate threats there are none.
+    fn possible_non_losing_moves(&self) -> u64 {
+        let mut possible = self.possible();
+        let opponent_wins = self.opponent_winning_cells();
+        let forced = possible & opponent_wins;
+        if forced != 0 {
+            if forced & (forced - 1) != 0 {
+                return 0;
+            }
+            possible = forced;
+        }
+        // Never play directly below an opponent's winning cell
+        possible & !(opponent_wins >> 1)
+    }
+
+    /// The moves in `possible`, ordered by how many winning cells they
+    /// create and then from the center outwards.
+    fn ordered_moves(&self, possible: u64) -> Vec<u64> {
+        let mut moves: Vec<(u32, usize, u64)> = column_order()
+            .enumerate()
+            .map(|(order, column)| (order, possible & column_mask(column)))
+            .filter(|&(_, chip)| chip != 0)
+            .map(|(order, chip)| {
+                let threats = winning_cells(self.current | chip, self.occupied).count_ones();
+                (threats, order, chip)
+            })
+            .collect();
+        moves.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        moves.into_iter().map(|(_, _, chip)| chip).collect()
+    }
+
+    fn winning_cells(&self) -> u64 {
+        winning_cells(self.current, self.occupied)
+    }
+
+    fn opponent_winning_cells(&self) -> u64 {
+        winning_cells(self.current ^ self.occupied, self.occupied)
+    }
+}
+
+/// Empty cells that would complete a line of four for `chips`.
+fn winning_cells(chips: u64, occupied: u64) -> u64 {
+    // Vertical lines can only be completed from above
+    let mut cells = (chips << 1) & (chips << 2) & (chips << 3);
+    for &shift in &[HEIGHT + 1, HEIGHT, HEIGHT + 2] {
+        let mut pair = (chips << shift) & (chips << (2 * shift));
+        cells |= pair & (chips << (3 * shift));
+        cells |= pair & (chips >> shift);
+        pair = (chips >> shift) & (chips >> (2 * shift));
+        cells |= pair & (chips << shift);
+        cells |= pair & (chips >> (3 * shift));
+    }
+    cells & (BOARD_MASK ^ occupied)
+}
+
+fn column_order() -> impl Iterator<Item = usize> {
+    (0..WIDTH).map(|i| {
+        if i % 2 == 0 {
+            WIDTH / 2 + i / 2
+        } else {
+            WIDTH / 2 - i.div_ceil(2)
+        }
+    })
+}
+
+const fn bottom_mask() -> u64 {
+    let mut mask = 0;
+    let mut column = 0;
+    while column < WIDTH {
+        mask |= bottom_mask_column(column);
+        column += 1;
+    }
+    mask
+}
+
+const fn bottom_mask_column(column: usize) -> u64 {
+    1 << (column * (HEIGHT + 1))
+}
+
+fn top_mask(column: usize) -> u64 {
+    (1 << (HEIGHT - 1)) << (column * (HEIGHT + 1))
+}
+
+fn column_mask(column: usize) -> u64 {
+    ((1 << HEIGHT) - 1) << (column * (HEIGHT + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_position_misses_the_table() -> Result<(), Error> {
+        let position = Position::from_game_state(&GameState::default())?;
+        let key = position.key();
+        let solver = Solver::new();
+        assert_ne!(solver.keys[(key % TABLE_SIZE as u64) as usize], key);
+        Ok(())
+    }
+}
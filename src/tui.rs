@@ -0,0 +1,368 @@
+use crate::cli::{self, Setup};
+use crate::game::*;
+use crate::player::Match;
+use crate::render::Renderer;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::Print;
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::io::{self, IsTerminal, Stdout, Write};
+use std::thread;
+use std::time::Duration;
+
+const FALL_DELAY: Duration = Duration::from_millis(40);
+
+const LEFT_PANEL_X: u16 = 1;
+const BOARD_X: u16 = 26;
+const PANEL_Y: u16 = 2;
+const HOVER_Y: u16 = PANEL_Y;
+const GRID_Y: u16 = HOVER_Y + 3;
+
+const HELP: &str = "←/→ move  Enter drop  p pop  0-F pick column  u undo  r redo  q quit";
+const FREE_PLACEMENT_HELP: &str =
+    "←/→/↑/↓ move  Enter place  0-F pick column  u undo  r redo  q quit";
+
+/// Plays in a full-screen terminal UI, or falls back to `cli::run` when
+/// stdin or stdout is not a terminal.
+pub fn run() {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return cli::run();
+    }
+    let setup = match cli::setup() {
+        Some(setup) => setup,
+        None => return,
+    };
+    let result = RawScreen::enter().and_then(|mut screen| Tui::new(setup).run(&mut screen.out));
+    if let Err(e) = result {
+        eprintln!("error: the terminal failed because {}", e);
+    }
+}
+
+/// Raw mode on the alternate screen, restored when dropped so that a panic
+/// does not leave the terminal unusable.
+struct RawScreen {
+    out: Stdout,
+}
+
+impl RawScreen {
+    fn enter() -> io::Result<RawScreen> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, EnterAlternateScreen, Hide)?;
+        Ok(RawScreen { out })
+    }
+}
+
+impl Drop for RawScreen {
+    fn drop(&mut self) {
+        let _ = execute!(self.out, Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+enum Key {
+    Left,
+    Right,
+    Up,
+    Down,
+    Column(usize),
+    Drop,
+    Pop,
+    Undo,
+    Redo,
+    Quit,
+}
+
+struct Tui {
+    game_match: Match,
+    computer_teams: Vec<Team>,
+    renderer: Renderer,
+    hover: usize,
+    /// The row of the cell to place on in a free placement game.
+    hover_row: usize,
+    status: String,
+}
+
+impl Tui {
+    fn new(setup: Setup) -> Tui {
+        let hover = setup.game_match.game().num_columns() / 2;
+        let hover_row = setup.game_match.game().num_rows() / 2;
+        Tui {
+            game_match: setup.game_match,
+            computer_teams: setup.computer_teams,
+            renderer: setup.renderer,
+            hover,
+            hover_row,
+            status: String::new(),
+        }
+    }
+
+    fn run(&mut self, out: &mut Stdout) -> io::Result<()> {
+        loop {
+            let game = self.game_match.game();
+            if !game.game_over() && self.computer_teams.contains(&game.cur_turn()) {
+                self.status = format!(
+                    "{} (computer) is thinking...",
+                    game.team_name(game.cur_turn())
+                );
+                self.draw(out)?;
+                let before = game.clone();
+                let mv = self
+                    .game_match
+                    .play_turn()
+                    .expect("computer chose an illegal move");
+                if let Move::Drop(column) = mv {
+                    self.animate_drop(out, &before, column)?;
+                }
+                self.status = format!("{} played {}.", before.team_name(before.cur_turn()), mv);
+                continue;
+            }
+            if game.game_over() {
+                self.status = end_message(game);
+            }
+            self.draw(out)?;
+            let key = match read_key()? {
+                Some(key) => key,
+                None => continue,
+            };
+            let game = self.game_match.game();
+            let num_columns = game.num_columns();
+            let num_rows = game.num_rows();
+            let free = game.placement() == Placement::Free;
+            match key {
+                Key::Left => self.hover = self.hover.saturating_sub(1),
+                Key::Right => self.hover = (self.hover + 1).min(num_columns - 1),
+                Key::Up if free => self.hover_row = (self.hover_row + 1).min(num_rows - 1),
+                Key::Up => {}
+                Key::Down if free => self.hover_row = self.hover_row.saturating_sub(1),
+                Key::Down => self.drop_chip(out)?,
+                Key::Column(column) if column < num_columns => self.hover = column,
+                Key::Column(_) => {}
+                Key::Drop => self.drop_chip(out)?,
+                Key::Pop => {
+                    let result = self.game_match.play_move(Move::Pop(self.hover));
+                    self.status = result_message(result, "Popped a chip out.");
+                }
+                Key::Undo => {
+                    let result =
+                        cli::undo_to_human_turn(&mut self.game_match, &self.computer_teams);
+                    self.status = result_message(result, "Undid the last move.");
+                }
+                Key::Redo => {
+                    let result = self.game_match.redo();
+                    self.status = result_message(result, "Redid the move.");
+                }
+                Key::Quit => return Ok(()),
+            }
+        }
+    }
+
+    fn drop_chip(&mut self, out: &mut Stdout) -> io::Result<()> {
+        if self.game_match.game().placement() == Placement::Free {
+            let result = self
+                .game_match
+                .play_move(Move::Place(self.hover_row, self.hover));
+            self.status = match result {
+                Ok(()) => String::new(),
+                Err(e) => format!("Can't place there because {}.", e),
+            };
+            return Ok(());
+        }
+        let before = self.game_match.game().clone();
+        match self.game_match.drop_chip(self.hover) {
+            Ok(()) => {
+                self.animate_drop(out, &before, self.hover)?;
+                self.status.clear();
+            }
+            Err(e) => self.status = format!("Can't drop there because {}.", e),
+        }
+        Ok(())
+    }
+
+    /// Shows the chip the current team of `before` dropped in `column`
+    /// falling to where it landed.
+    fn animate_drop(&self, out: &mut Stdout, before: &GameState, column: usize) -> io::Result<()> {
+        let team = before.cur_turn();
+        let landed = before.column_height(column).unwrap_or(0);
+        for row in (landed..before.num_rows()).rev() {
+            draw_game(out, before, self.renderer, &[])?;
+            let (x, y) = cell_position(before, self.renderer, row, column);
+            queue!(
+                out,
+                MoveTo(x, y),
+                Print(self.renderer.team_glyph(before, team))
+            )?;
+            out.flush()?;
+            thread::sleep(FALL_DELAY);
+        }
+        Ok(())
+    }
+
+    fn draw(&self, out: &mut Stdout) -> io::Result<()> {
+        let game = self.game_match.game();
+        let mut highlighted: Vec<_> = game
+            .winning_lines()
+            .iter()
+            .flat_map(|line| line.cells.iter().cloned())
+            .collect();
+        let humans_turn = !game.game_over() && !self.computer_teams.contains(&game.cur_turn());
+        let free = game.placement() == Placement::Free;
+        if humans_turn && free {
+            highlighted.push((self.hover_row, self.hover));
+        }
+        draw_game(out, game, self.renderer, &highlighted)?;
+        if humans_turn && !free {
+            let x = column_x(game, self.renderer, self.hover);
+            let glyph = self.renderer.team_glyph(game, game.cur_turn());
+            queue!(out, MoveTo(x, HOVER_Y), Print(glyph))?;
+        }
+        let status_y = PANEL_Y + game.num_teams() as u16 + 3;
+        queue!(out, MoveTo(LEFT_PANEL_X, status_y), Print("Status"))?;
+        for (i, line) in self.status.lines().enumerate() {
+            queue!(
+                out,
+                MoveTo(LEFT_PANEL_X, status_y + 1 + i as u16),
+                Print(line)
+            )?;
+        }
+        out.flush()
+    }
+}
+
+/// Draws everything but the hovering chip and the status: the turn panel,
+/// the board, the move list and the key help.
+fn draw_game(
+    out: &mut Stdout,
+    game: &GameState,
+    renderer: Renderer,
+    highlighted: &[(usize, usize)],
+) -> io::Result<()> {
+    queue!(
+        out,
+        Clear(ClearType::All),
+        MoveTo(LEFT_PANEL_X, 0),
+        Print("Connect Four"),
+        MoveTo(LEFT_PANEL_X, PANEL_Y),
+        Print("Turn")
+    )?;
+    for (i, team) in (0..game.num_teams()).map(Team::new).enumerate() {
+        let marker = if !game.game_over() && team == game.cur_turn() {
+            '>'
+        } else {
+            ' '
+        };
+        queue!(
+            out,
+            MoveTo(LEFT_PANEL_X, PANEL_Y + 1 + i as u16),
+            Print(format!(
+                "{} {} {}",
+                marker,
+                renderer.team_glyph(game, team),
+                game.team_name(team)
+            ))
+        )?;
+    }
+
+    // The renderer's header, separator and rows, without its legend.
+    let board = renderer.render(game, highlighted);
+    for (i, line) in board.lines().take(game.num_rows() + 2).enumerate() {
+        queue!(out, MoveTo(BOARD_X, GRID_Y - 2 + i as u16), Print(line))?;
+    }
+
+    // Some terminals report no size, so always leave room for the board.
+    let (_, height) = terminal::size()?;
+    let height = height.max(GRID_Y + game.num_rows() as u16 + 2);
+    let moves_x =
+        column_x(game, renderer, game.num_columns()) + 2 + renderer.row_label_width(game) as u16;
+    let room = (height as usize).saturating_sub(PANEL_Y as usize + 3);
+    let history = game.history();
+    queue!(out, MoveTo(moves_x, PANEL_Y), Print("Moves"))?;
+    let shown = history
+        .iter()
+        .enumerate()
+        .skip(history.len().saturating_sub(room));
+    for (line, (i, played)) in shown.enumerate() {
+        queue!(
+            out,
+            MoveTo(moves_x, PANEL_Y + 1 + line as u16),
+            Print(format!(
+                "{:>3}. {} {}",
+                i + 1,
+                renderer.team_glyph(game, played.team),
+                move_label(played.to_move())
+            ))
+        )?;
+    }
+
+    queue!(
+        out,
+        MoveTo(LEFT_PANEL_X, height.saturating_sub(1)),
+        Print(match game.placement() {
+            Placement::Gravity => HELP,
+            Placement::Free => FREE_PLACEMENT_HELP,
+        })
+    )
+}
+
+/// Where a cell of the board is drawn on the screen.
+fn cell_position(game: &GameState, renderer: Renderer, row: usize, column: usize) -> (u16, u16) {
+    let y = GRID_Y + (game.num_rows() - 1 - row) as u16;
+    (column_x(game, renderer, column), y)
+}
+
+/// The screen column where a board column starts.
+fn column_x(game: &GameState, renderer: Renderer, column: usize) -> u16 {
+    BOARD_X + ((renderer.column_width(game) + 1) * column) as u16
+}
+
+/// Waits for a key press, returning `None` for keys that do nothing.
+fn read_key() -> io::Result<Option<Key>> {
+    let key = match event::read()? {
+        Event::Key(key) if key.kind == KeyEventKind::Press => key,
+        _ => return Ok(None),
+    };
+    let key = match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Key::Quit,
+        KeyCode::Left | KeyCode::Char('h') => Key::Left,
+        KeyCode::Right | KeyCode::Char('l') => Key::Right,
+        KeyCode::Up | KeyCode::Char('k') => Key::Up,
+        KeyCode::Down | KeyCode::Char('j') => Key::Down,
+        KeyCode::Enter | KeyCode::Char(' ') => Key::Drop,
+        KeyCode::Char('p') => Key::Pop,
+        KeyCode::Char('u') => Key::Undo,
+        KeyCode::Char('r') => Key::Redo,
+        KeyCode::Char('q') | KeyCode::Esc => Key::Quit,
+        KeyCode::Char(c) => match c.to_digit(16) {
+            Some(column) => Key::Column(column as usize),
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(key))
+}
+
+/// A move as shown in the move list: the column, prefixed with `p` for pops,
+/// or the row and column for places.
+fn move_label(mv: Move) -> String {
+    match mv {
+        Move::Drop(column) => format!("{:X}", column),
+        Move::Pop(column) => format!("p{:X}", column),
+        Move::Place(row, column) => format!("{:X},{:X}", row, column),
+    }
+}
+
+/// The outcome, after the ranking in a game scored by counting lines. One
+/// status line each.
+fn end_message(game: &GameState) -> String {
+    let mut lines = cli::ranking_lines(game);
+    lines.push(format!("{} Press q to quit.", cli::outcome_message(game)));
+    lines.join("\n")
+}
+
+fn result_message(result: Result<(), Error>, success: &str) -> String {
+    match result {
+        Ok(()) => success.to_string(),
+        Err(e) => format!("Can't do that because {}.", e),
+    }
+}
@@ -0,0 +1,102 @@
+extern crate connect_four;
+
+use connect_four::ai::*;
+use connect_four::game::*;
+
+#[test]
+fn takes_winning_move() -> Result<(), Error> {
+    let mut game = GameState::default();
+    for _ in 0..DEFAULT_WINNING_LENGTH - 1 {
+        game.drop_chip(Team::new(0), 0)?;
+        game.drop_chip(Team::new(1), 6)?;
+    }
+    let ai = Ai::new(3);
+    assert_eq!(ai.choose_column(&game), Some(0));
+    Ok(())
+}
+
+#[test]
+fn blocks_opponent_win() -> Result<(), Error> {
+    let mut game = GameState::default();
+    game.drop_chip(Team::new(0), 0)?;
+    game.drop_chip(Team::new(1), 0)?;
+    game.drop_chip(Team::new(0), 1)?;
+    game.drop_chip(Team::new(1), 1)?;
+    game.drop_chip(Team::new(0), 2)?;
+    // Team 0 has three in a row against the wall and threatens column 3
+    let ai = Ai::new(4);
+    assert_eq!(ai.choose_column(&game), Some(3));
+    Ok(())
+}
+
+#[test]
+fn takes_winning_move_around_the_edge() -> Result<(), MoveStringError> {
+    let config = GameConfig {
+        topology: Topology::Cylinder,
+        ..GameConfig::default()
+    };
+    // Team 1 threatens column 3, but team 0 can win across the wrap first
+    let game = GameState::from_moves("647414", &config)?;
+    let ai = Ai::new(4);
+    let column = ai.choose_column(&game).unwrap();
+    assert_eq!(game.play(column).unwrap().who_won(), Some(Team::new(0)));
+    Ok(())
+}
+
+#[test]
+fn blocks_in_tic_tac_toe() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        num_rows: 3,
+        num_columns: 3,
+        winning_length: 3,
+        placement: Placement::Free,
+        ..GameConfig::default()
+    })?;
+    game.place_chip(Team::new(0), 0, 0)?;
+    game.place_chip(Team::new(1), 1, 1)?;
+    game.place_chip(Team::new(0), 0, 2)?;
+    let ai = Ai::new(4);
+    assert_eq!(ai.best_move(&game), Some(Move::Place(0, 1)));
+    Ok(())
+}
+
+#[test]
+fn custom_evaluator() -> Result<(), Error> {
+    let game = GameState::default();
+    let ai = Ai::with_evaluator(2, |_: &GameState, _: Team| 0);
+    assert!(ai.choose_column(&game).is_some());
+    Ok(())
+}
+
+#[test]
+fn plays_multi_team_game() -> Result<(), Error> {
+    let mut game = GameState::new(Team::new(0), 3, 5, 5, 3)?;
+    let ai = Ai::new(3);
+    while !game.game_over() {
+        let team = game.cur_turn();
+        let column = ai.choose_column(&game).unwrap();
+        game.drop_chip(team, column)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn narrow_board() -> Result<(), Error> {
+    let game = GameState::new(Team::new(0), 2, 3, 8, 5)?;
+    assert!(Ai::new(2).choose_column(&game).is_some());
+    Ok(())
+}
+
+#[test]
+fn no_moves_when_game_over() -> Result<(), Error> {
+    let mut game = GameState::default();
+    for i in 0..DEFAULT_WINNING_LENGTH {
+        game.drop_chip(Team::new(0), 0)?;
+        if i < DEFAULT_WINNING_LENGTH - 1 {
+            game.drop_chip(Team::new(1), 1)?;
+        }
+    }
+    let ai = Ai::new(DEFAULT_DEPTH);
+    assert_eq!(ai.choose_column(&game), None);
+    Ok(())
+}
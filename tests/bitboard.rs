@@ -0,0 +1,153 @@
+extern crate connect_four;
+
+use connect_four::bitboard::*;
+use connect_four::game::*;
+
+/// Plays the same pseudo-random game on a `GameState` and a `BitBoard`,
+/// checking that they agree after every move.
+fn play_alongside(seed: u64, num_rows: usize, num_columns: usize, winning_length: usize) {
+    let mut game = GameState::new(Team::new(0), 2, num_rows, num_columns, winning_length).unwrap();
+    let mut board = BitBoard::new(Team::new(0), num_rows, num_columns, winning_length).unwrap();
+    let mut state = seed;
+    while !game.game_over() {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let column = (state >> 33) as usize % num_columns;
+        let team = game.cur_turn();
+        assert_eq!(
+            game.drop_chip(team, column).map(|_| ()),
+            board.drop_chip(team, column)
+        );
+        assert_eq!(game.outcome(), board.outcome());
+        assert_eq!(game.cur_turn(), board.cur_turn());
+        assert_eq!(game.to_string_arr(), board.to_string_arr());
+    }
+}
+
+#[test]
+fn matches_game_state() {
+    for seed in 0..50 {
+        play_alongside(
+            seed,
+            DEFAULT_NUM_ROWS,
+            DEFAULT_NUM_COLUMNS,
+            DEFAULT_WINNING_LENGTH,
+        );
+        play_alongside(seed, 4, 5, 3);
+        play_alongside(seed, 7, 7, 5);
+        play_alongside(seed, 8, 8, 4);
+        play_alongside(seed, 1, 64, 3);
+        play_alongside(seed, 64, 1, 4);
+    }
+}
+
+#[test]
+fn round_trip_through_game_state() -> Result<(), Error> {
+    let mut game = GameState::default();
+    for &column in &[3, 3, 4, 2, 0, 6] {
+        let team = game.cur_turn();
+        game.drop_chip(team, column)?;
+    }
+    let board = BitBoard::from_game_state(&game)?;
+    assert_eq!(board.num_moves(), 6);
+    assert_eq!(board.cur_turn(), game.cur_turn());
+    let converted = board.to_game_state();
+    assert_eq!(converted.to_string_arr(), game.to_string_arr());
+    assert_eq!(converted.moves_played(), 6);
+    assert_eq!(BitBoard::from_game_state(&converted)?, board);
+    Ok(())
+}
+
+#[test]
+fn converts_won_game() -> Result<(), Error> {
+    let mut game = GameState::default();
+    for i in 0..DEFAULT_WINNING_LENGTH {
+        game.drop_chip(Team::new(0), 0)?;
+        if i < DEFAULT_WINNING_LENGTH - 1 {
+            game.drop_chip(Team::new(1), 1)?;
+        }
+    }
+    let board = BitBoard::from_game_state(&game)?;
+    assert_eq!(board.who_won(), Some(Team::new(0)));
+    assert!(board.game_over());
+    Ok(())
+}
+
+#[test]
+fn rejects_unsupported_boards() -> Result<(), Error> {
+    assert_eq!(
+        BitBoard::new(Team::new(0), 9, 8, 4),
+        Err(Error::UnsupportedBoard)
+    );
+    assert_eq!(
+        BitBoard::new(Team::new(0), 1, 65, 4),
+        Err(Error::UnsupportedBoard)
+    );
+    let unsupported = [
+        GameConfig {
+            num_teams: 3,
+            ..GameConfig::default()
+        },
+        GameConfig {
+            pop_out: true,
+            ..GameConfig::default()
+        },
+        GameConfig {
+            topology: Topology::Cylinder,
+            ..GameConfig::default()
+        },
+        GameConfig {
+            placement: Placement::Free,
+            ..GameConfig::default()
+        },
+        GameConfig {
+            win_rule: WinRule::Exactly,
+            ..GameConfig::default()
+        },
+        GameConfig {
+            win_rule: WinRule::OverlineLoses,
+            ..GameConfig::default()
+        },
+        GameConfig {
+            scoring: ScoringMode::CountLines,
+            ..GameConfig::default()
+        },
+    ];
+    for config in &unsupported {
+        let game = GameState::from_config(config)?;
+        assert_eq!(
+            BitBoard::from_game_state(&game),
+            Err(Error::UnsupportedBoard),
+            "{:?}",
+            config
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn converts_boards_up_to_64_cells() -> Result<(), Error> {
+    let config = GameConfig {
+        num_rows: 8,
+        num_columns: 8,
+        ..GameConfig::default()
+    };
+    let game = GameState::from_moves("8787878", &config).unwrap();
+    let board = BitBoard::from_game_state(&game)?;
+    assert_eq!(board.who_won(), Some(Team::new(0)));
+    assert_eq!(board.to_string_arr(), game.to_string_arr());
+    Ok(())
+}
+
+#[test]
+fn different_positions_have_different_keys() -> Result<(), Error> {
+    let mut first = BitBoard::default();
+    let mut second = BitBoard::default();
+    first.drop_chip(Team::new(0), 0)?;
+    first.drop_chip(Team::new(1), 1)?;
+    second.drop_chip(Team::new(0), 1)?;
+    second.drop_chip(Team::new(1), 0)?;
+    assert_ne!(first.key(), second.key());
+    Ok(())
+}
@@ -110,6 +110,57 @@ fn win_diagonally_up_left() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn winning_line_horizontal() -> Result<(), Error> {
+    let mut game = GameState::default();
+    for i in 0..DEFAULT_WINNING_LENGTH {
+        game.drop_chip(Team::new(0), i)?;
+        if i < DEFAULT_WINNING_LENGTH - 1 {
+            game.drop_chip(Team::new(1), i)?;
+        }
+    }
+    assert_eq!(
+        game.winning_lines(),
+        &[WinningLine {
+            team: Team::new(0),
+            direction: Direction::Horizontal,
+            cells: vec![(0, 0), (0, 1), (0, 2), (0, 3)],
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn winning_line_up_left() -> Result<(), MoveStringError> {
+    // Same moves as win_diagonally_up_left
+    let game = GameState::from_moves_zero_based("32211610000", &GameConfig::default())?;
+    assert_eq!(
+        game.winning_lines(),
+        &[WinningLine {
+            team: Team::new(0),
+            direction: Direction::UpLeft,
+            cells: vec![(0, 3), (1, 2), (2, 1), (3, 0)],
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn winning_lines_through_same_chip() -> Result<(), MoveStringError> {
+    // Team 0's last chip completes a horizontal and a diagonal line at once
+    let mut game = GameState::from_moves_zero_based("1314304442362", &GameConfig::default())?;
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    let directions: Vec<_> = game
+        .winning_lines()
+        .iter()
+        .map(|line| line.direction)
+        .collect();
+    assert_eq!(directions, vec![Direction::Horizontal, Direction::UpRight]);
+    game.undo().unwrap();
+    assert!(game.winning_lines().is_empty());
+    Ok(())
+}
+
 #[test]
 fn drop_out_of_bounds() {
     let mut game = GameState::default();
@@ -182,6 +233,197 @@ fn no_drops_after_game_over() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn draw_when_board_full() -> Result<(), MoveStringError> {
+    // Only valid for the default board, fills it without anyone connecting four
+    let moves = "545062455041104565311226266362030334314210";
+    let mut game =
+        GameState::from_moves_zero_based(&moves[..moves.len() - 1], &GameConfig::default())?;
+    assert_eq!(game.outcome(), GameOutcome::InProgress);
+    let cur_turn = game.cur_turn();
+    game.drop_chip(cur_turn, 0).unwrap();
+    assert!(game.game_over());
+    assert_eq!(game.outcome(), GameOutcome::Draw);
+    assert_eq!(game.who_won(), None);
+    let cur_turn = game.cur_turn();
+    let result = game.drop_chip(cur_turn, 0);
+    assert_eq!(result, Err(Error::GameOver));
+    Ok(())
+}
+
+#[test]
+fn won_outcome() -> Result<(), Error> {
+    let mut game = GameState::default();
+    for i in 0..DEFAULT_WINNING_LENGTH {
+        game.drop_chip(Team::new(0), 0)?;
+        if i < DEFAULT_WINNING_LENGTH - 1 {
+            game.drop_chip(Team::new(1), 1)?;
+        }
+    }
+    assert_eq!(game.outcome(), GameOutcome::Won(Team::new(0)));
+    Ok(())
+}
+
+#[test]
+fn undo_and_redo() -> Result<(), Error> {
+    let mut game = GameState::default();
+    let empty = game.to_string_arr();
+    game.drop_chip(Team::new(0), 3)?;
+    let after_first = game.to_string_arr();
+    game.drop_chip(Team::new(1), 3)?;
+    let after_second = game.to_string_arr();
+    assert_eq!(game.history().len(), 2);
+
+    game.undo()?;
+    assert_eq!(game.to_string_arr(), after_first);
+    assert_eq!(game.cur_turn(), Team::new(1));
+    game.undo()?;
+    assert_eq!(game.to_string_arr(), empty);
+    assert_eq!(game.cur_turn(), Team::new(0));
+    assert!(game.history().is_empty());
+    assert_eq!(game.undo(), Err(Error::NothingToUndo));
+
+    game.redo()?;
+    game.redo()?;
+    assert_eq!(game.to_string_arr(), after_second);
+    assert_eq!(game.cur_turn(), Team::new(0));
+    assert_eq!(
+        game.history(),
+        &[
+            PlayedMove {
+                team: Team::new(0),
+                row: 0,
+                column: 3,
+                kind: MoveKind::Drop,
+            },
+            PlayedMove {
+                team: Team::new(1),
+                row: 1,
+                column: 3,
+                kind: MoveKind::Drop,
+            },
+        ]
+    );
+    assert_eq!(game.redo(), Err(Error::NothingToRedo));
+    Ok(())
+}
+
+#[test]
+fn new_move_clears_redo() -> Result<(), Error> {
+    let mut game = GameState::default();
+    game.drop_chip(Team::new(0), 0)?;
+    game.undo()?;
+    game.drop_chip(Team::new(0), 1)?;
+    assert_eq!(game.redo(), Err(Error::NothingToRedo));
+    Ok(())
+}
+
+#[test]
+fn undo_after_win() -> Result<(), Error> {
+    let mut game = GameState::default();
+    for i in 0..DEFAULT_WINNING_LENGTH {
+        game.drop_chip(Team::new(0), 0)?;
+        if i < DEFAULT_WINNING_LENGTH - 1 {
+            game.drop_chip(Team::new(1), 1)?;
+        }
+    }
+    assert!(game.game_over());
+    game.undo()?;
+    assert!(!game.game_over());
+    assert_eq!(game.cur_turn(), Team::new(0));
+    game.drop_chip(Team::new(0), 2)?;
+    assert!(!game.game_over());
+    Ok(())
+}
+
+#[test]
+fn move_string_round_trip() -> Result<(), MoveStringError> {
+    let game = GameState::from_moves("4453", &GameConfig::default())?;
+    let columns: Vec<_> = game.history().iter().map(|played| played.column).collect();
+    assert_eq!(columns, vec![3, 3, 4, 2]);
+    assert_eq!(game.to_move_string(), Ok("4453".to_string()));
+    assert_eq!(game.to_move_string_zero_based(), Ok("3342".to_string()));
+    let zero_based = GameState::from_moves_zero_based("3342", &GameConfig::default())?;
+    assert_eq!(zero_based.to_string_arr(), game.to_string_arr());
+    Ok(())
+}
+
+#[test]
+fn move_string_wide_board() -> Result<(), MoveStringError> {
+    let config = GameConfig {
+        num_columns: 12,
+        ..GameConfig::default()
+    };
+    let game = GameState::from_moves("1ac", &config)?;
+    let columns: Vec<_> = game.history().iter().map(|played| played.column).collect();
+    assert_eq!(columns, vec![0, 9, 11]);
+    assert_eq!(game.to_move_string(), Ok("1ac".to_string()));
+    Ok(())
+}
+
+#[test]
+fn move_string_errors() {
+    let config = GameConfig::default();
+    assert_eq!(
+        GameState::from_moves("44!3", &config).err(),
+        Some(MoveStringError::InvalidCharacter(2, '!'))
+    );
+    assert_eq!(
+        GameState::from_moves("440", &config).err(),
+        Some(MoveStringError::InvalidCharacter(2, '0'))
+    );
+    assert_eq!(
+        GameState::from_moves("44z", &config).err(),
+        Some(MoveStringError::InvalidMove(2, Error::OutOfBounds))
+    );
+    assert_eq!(
+        GameState::from_moves("1111111", &config).err(),
+        Some(MoveStringError::InvalidMove(6, Error::ColumnFull))
+    );
+    assert_eq!(
+        GameState::from_moves("12121212", &config).err(),
+        Some(MoveStringError::InvalidMove(7, Error::GameOver))
+    );
+    let no_teams = GameConfig {
+        num_teams: 0,
+        ..config
+    };
+    assert_eq!(
+        GameState::from_moves("", &no_teams).err(),
+        Some(MoveStringError::InvalidConfig(Error::NoTeams))
+    );
+}
+
+#[test]
+fn config_round_trip() -> Result<(), Error> {
+    let config = GameConfig {
+        first_turn: Team::new(2),
+        num_teams: 3,
+        num_rows: 10,
+        num_columns: 9,
+        winning_length: 6,
+        pop_out: true,
+        topology: Topology::Cylinder,
+        placement: Placement::Gravity,
+        win_rule: WinRule::Exactly,
+        scoring: ScoringMode::FirstLine,
+    };
+    assert_eq!(GameState::from_config(&config)?.config(), config);
+    let config = GameConfig {
+        pop_out: false,
+        placement: Placement::Free,
+        ..config
+    };
+    assert_eq!(GameState::from_config(&config)?.config(), config);
+    let config = GameConfig {
+        win_rule: WinRule::AtLeast,
+        scoring: ScoringMode::CountLines,
+        ..config
+    };
+    assert_eq!(GameState::from_config(&config)?.config(), config);
+    Ok(())
+}
+
 #[test]
 fn custom_game() -> Result<(), Error> {
     let winning_length = 6;
@@ -197,6 +439,268 @@ fn custom_game() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn invalid_parameters() {
+    let new = |first_turn, num_teams, num_rows, num_columns, winning_length| {
+        GameState::new(
+            Team::new(first_turn),
+            num_teams,
+            num_rows,
+            num_columns,
+            winning_length,
+        )
+        .err()
+    };
+    assert_eq!(new(0, 0, 6, 7, 4), Some(Error::NoTeams));
+    assert_eq!(new(2, 2, 6, 7, 4), Some(Error::InvalidTeam));
+    assert_eq!(new(0, 2, 0, 7, 4), Some(Error::InvalidDimensions));
+    assert_eq!(new(0, 2, 6, 0, 4), Some(Error::InvalidDimensions));
+    assert_eq!(new(0, 2, 6, 7, 0), Some(Error::InvalidWinningLength));
+    assert_eq!(new(0, 2, 6, 7, 8), Some(Error::InvalidWinningLength));
+    assert_eq!(new(1, 2, 6, 7, 7), None);
+}
+
+#[test]
+fn winning_length_longer_than_columns() -> Result<(), Error> {
+    let mut game = GameState::new(Team::new(0), 2, 5, 3, 5)?;
+    for _ in 0..4 {
+        game.drop_chip(Team::new(0), 0)?;
+        game.drop_chip(Team::new(1), 1)?;
+    }
+    game.drop_chip(Team::new(0), 0)?;
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    Ok(())
+}
+
+#[test]
+fn error_messages() {
+    let error: Box<dyn std::error::Error> = Box::new(Error::InvalidWinningLength);
+    assert_eq!(
+        error.to_string(),
+        "the winning length must be at least one and fit on the board"
+    );
+    assert_eq!(Error::ColumnFull.to_string(), "that column is full");
+}
+
+#[test]
+fn large_custom_game() -> Result<(), Error> {
+    let num_teams = 16;
+    let winning_length = 5;
+    let mut game = GameState::new(Team::new(0), num_teams, 100, 100, winning_length)?;
+    // Every team stacks its own column until team 0 completes a vertical line
+    for _ in 0..winning_length - 1 {
+        for team in 0..num_teams {
+            game.drop_chip(Team::new(team), team * 2)?;
+        }
+    }
+    assert!(!game.game_over());
+    game.drop_chip(Team::new(0), 0)?;
+    assert!(game.game_over());
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    assert!(game.has_won(Team::new(0)));
+    assert!(!game.has_won(Team::new(1)));
+    Ok(())
+}
+
+#[test]
+fn team_info() -> Result<(), Error> {
+    let mut game = GameState::default();
+    let team = Team::new(1);
+    assert_eq!(game.team_name(team), "Team 1");
+    assert_eq!(game.team_info(team).glyph, "1");
+    assert_eq!(game.team_info(team).color, None);
+
+    let info = TeamInfo {
+        name: "Yellow".to_string(),
+        glyph: "Y".to_string(),
+        color: Some(226),
+    };
+    game.set_team_info(team, info.clone())?;
+    assert_eq!(game.team_info(team), &info);
+    game.drop_chip(Team::new(0), 0)?;
+    game.drop_chip(team, 1)?;
+    assert_eq!(game.to_string_arr()[0], "0Y_____");
+
+    let with = |name: &str, glyph: &str| TeamInfo {
+        name: name.to_string(),
+        glyph: glyph.to_string(),
+        color: None,
+    };
+    let red = Team::new(0);
+    assert_eq!(
+        game.set_team_info(red, with("", "R")),
+        Err(Error::InvalidTeamName)
+    );
+    assert_eq!(
+        game.set_team_info(red, with("a\nb", "R")),
+        Err(Error::InvalidTeamName)
+    );
+    assert_eq!(
+        game.set_team_info(red, with("Red", "Y")),
+        Err(Error::InvalidGlyph)
+    );
+    assert_eq!(
+        game.set_team_info(red, with("Red", "_")),
+        Err(Error::InvalidGlyph)
+    );
+    assert_eq!(
+        game.set_team_info(red, with("Red", " ")),
+        Err(Error::InvalidGlyph)
+    );
+    assert_eq!(
+        game.set_team_info(Team::new(2), with("Red", "R")),
+        Err(Error::InvalidTeam)
+    );
+    Ok(())
+}
+
+#[test]
+fn many_teams() -> Result<(), Error> {
+    let mut game = GameState::new(Team::new(0), 40, 2, 20, 2)?;
+    assert_eq!(game.cell_width(), 2);
+    assert_eq!(game.team_info(Team::new(15)).glyph, "f");
+    assert_eq!(game.team_info(Team::new(35)).glyph, "z");
+    assert_eq!(game.team_info(Team::new(36)).glyph, "10");
+    for column in 0..20 {
+        let team = game.cur_turn();
+        game.drop_chip(team, column)?;
+    }
+    for column in 0..17 {
+        let team = game.cur_turn();
+        game.drop_chip(team, column)?;
+    }
+    let rows = game.to_string_arr();
+    assert_eq!(rows[0], " 0 1 2 3 4 5 6 7 8 9 a b c d e f g h i j");
+    assert_eq!(rows[1], " k l m n o p q r s t u v w x y z10______");
+    Ok(())
+}
+
+#[test]
+fn long_glyphs_widen_cells() -> Result<(), Error> {
+    let mut game = GameState::default();
+    let info = TeamInfo {
+        name: "Stars".to_string(),
+        glyph: "**".to_string(),
+        color: None,
+    };
+    game.set_team_info(Team::new(0), info)?;
+    game.drop_chip(Team::new(0), 0)?;
+    game.drop_chip(Team::new(1), 1)?;
+    assert_eq!(game.cell_width(), 2);
+    assert_eq!(game.to_string_arr()[0], "** 1__________");
+    Ok(())
+}
+
+#[test]
+fn move_events() -> Result<(), Error> {
+    let red = Team::new(0);
+    let yellow = Team::new(1);
+    let mut game = GameState::new(red, 2, 1, 3, 2)?;
+    assert_eq!(
+        game.drop_chip(red, 0)?,
+        vec![
+            GameEvent::ChipDropped {
+                team: red,
+                row: 0,
+                column: 0
+            },
+            GameEvent::TurnChanged { team: yellow },
+        ]
+    );
+    assert_eq!(
+        game.undo()?,
+        vec![
+            GameEvent::MoveUndone {
+                team: red,
+                row: 0,
+                column: 0
+            },
+            GameEvent::TurnChanged { team: red },
+        ]
+    );
+    game.redo()?;
+    game.drop_chip(yellow, 2)?;
+    let events = game.drop_chip(red, 1)?;
+    assert_eq!(events.len(), 2);
+    assert_eq!(
+        events[1],
+        GameEvent::GameWon {
+            team: red,
+            line: game.winning_lines()[0].clone()
+        }
+    );
+
+    let mut game = GameState::new(red, 2, 1, 2, 2)?;
+    game.drop_chip(red, 0)?;
+    assert_eq!(
+        game.drop_chip(yellow, 1)?.last(),
+        Some(&GameEvent::GameDrawn)
+    );
+    Ok(())
+}
+
+/// The only window for this line starts in the top row and runs down to
+/// the right.
+#[test]
+fn windows_reach_the_top_row() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        num_rows: 4,
+        num_columns: 4,
+        placement: Placement::Free,
+        ..GameConfig::default()
+    })?;
+    for &(row, column) in &[(3, 0), (0, 0), (2, 1), (0, 1), (1, 2), (1, 0), (0, 3)] {
+        let team = game.cur_turn();
+        game.place_chip(team, row, column)?;
+    }
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    assert_eq!(game.scores(), vec![(Team::new(0), 1), (Team::new(1), 0)]);
+    Ok(())
+}
+
+#[test]
+fn position_queries() -> Result<(), Error> {
+    let mut game = GameState::new(Team::new(0), 2, 2, 3, 2)?;
+    assert_eq!(game.legal_moves(), vec![0, 1, 2]);
+    assert_eq!(game.moves_played(), 0);
+    game.drop_chip(Team::new(0), 1)?;
+    game.drop_chip(Team::new(1), 1)?;
+    assert_eq!(game.column_height(0), Ok(0));
+    assert_eq!(game.column_height(1), Ok(2));
+    assert_eq!(game.column_height(3), Err(Error::OutOfBounds));
+    assert_eq!(game.cell(0, 1), Some(Team::new(0)));
+    assert_eq!(game.cell(1, 1), Some(Team::new(1)));
+    assert_eq!(game.cell(0, 0), None);
+    assert_eq!(game.cell(2, 0), None);
+    assert_eq!(game.legal_moves(), vec![0, 2]);
+    assert_eq!(game.moves_played(), 2);
+    assert!(!game.is_full());
+
+    game.drop_chip(Team::new(0), 2)?;
+    assert!(game.game_over());
+    assert!(game.legal_moves().is_empty());
+    Ok(())
+}
+
+#[test]
+fn immutable_play() -> Result<(), Error> {
+    let game = GameState::default();
+    let next = game.play(3)?;
+    assert_eq!(game.moves_played(), 0);
+    assert_eq!(next.moves_played(), 1);
+    assert_eq!(next.cell(0, 3), Some(Team::new(0)));
+    assert_eq!(next.cur_turn(), Team::new(1));
+    assert_eq!(game.play(7).err(), Some(Error::OutOfBounds));
+
+    let mut full_column = game;
+    for _ in 0..DEFAULT_NUM_ROWS {
+        full_column = full_column.play(0)?;
+    }
+    assert_eq!(full_column.column_height(0), Ok(DEFAULT_NUM_ROWS));
+    assert_eq!(full_column.play(0).err(), Some(Error::ColumnFull));
+    Ok(())
+}
+
 #[test]
 #[ignore]
 fn sample_game() -> Result<(), Error> {
@@ -223,8 +727,7 @@ fn sample_game() -> Result<(), Error> {
     println!("final grid:");
     print_grid(&game, "");
     // Quit so we can see
-    assert!(false);
-    Ok(())
+    panic!("quit so we can see the output");
 }
 
 fn print_grid(game: &GameState, prefix: &str) {
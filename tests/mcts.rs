@@ -0,0 +1,63 @@
+extern crate connect_four;
+
+use connect_four::game::*;
+use connect_four::mcts::*;
+use std::time::Duration;
+
+#[test]
+fn takes_winning_move() -> Result<(), MoveStringError> {
+    let game = GameState::from_moves("171717", &GameConfig::default())?;
+    let mut mcts = Mcts::new(Budget::Iterations(2_000), 1);
+    assert_eq!(mcts.choose_column(&game), Some(0));
+    Ok(())
+}
+
+#[test]
+fn blocks_opponent_win() -> Result<(), MoveStringError> {
+    let game = GameState::from_moves("11223", &GameConfig::default())?;
+    let mut mcts = Mcts::new(Budget::Iterations(2_000), 1);
+    assert_eq!(mcts.choose_column(&game), Some(3));
+    Ok(())
+}
+
+#[test]
+fn same_seed_same_moves() -> Result<(), Error> {
+    let config = GameConfig {
+        num_teams: 3,
+        num_rows: 8,
+        num_columns: 9,
+        ..GameConfig::default()
+    };
+    let play = |seed| {
+        let mut game = GameState::from_config(&config).unwrap();
+        let mut mcts = Mcts::new(Budget::Iterations(200), seed);
+        for _ in 0..10 {
+            let team = game.cur_turn();
+            let column = mcts.choose_column(&game).unwrap();
+            game.drop_chip(team, column).unwrap();
+        }
+        game.to_string_arr()
+    };
+    assert_eq!(play(7), play(7));
+    Ok(())
+}
+
+#[test]
+fn plays_four_teams_on_large_board() -> Result<(), Error> {
+    let mut game = GameState::new(Team::new(0), 4, 10, 12, 4)?;
+    let mut mcts = Mcts::new(Budget::Iterations(50), 3);
+    while !game.game_over() {
+        let team = game.cur_turn();
+        let column = mcts.choose_column(&game).unwrap();
+        game.drop_chip(team, column)?;
+    }
+    assert_eq!(mcts.choose_column(&game), None);
+    Ok(())
+}
+
+#[test]
+fn time_budget() {
+    let game = GameState::default();
+    let mut mcts = Mcts::new(Budget::Time(Duration::from_millis(20)), 0);
+    assert!(mcts.choose_column(&game).is_some());
+}
@@ -0,0 +1,175 @@
+extern crate connect_four;
+
+use connect_four::game::*;
+use connect_four::player::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Default)]
+struct Log {
+    opponent_moves: Vec<(Team, Move)>,
+    games_ended: usize,
+    undone: Vec<(Team, Move)>,
+    games_replaced: usize,
+}
+
+struct Scripted {
+    columns: Vec<usize>,
+    log: Rc<RefCell<Log>>,
+}
+
+impl Scripted {
+    fn new(columns: &[usize]) -> (Scripted, Rc<RefCell<Log>>) {
+        let log = Rc::new(RefCell::new(Log::default()));
+        let player = Scripted {
+            columns: columns.iter().rev().cloned().collect(),
+            log: Rc::clone(&log),
+        };
+        (player, log)
+    }
+}
+
+impl Player for Scripted {
+    fn choose_move(&mut self, _game: &GameState) -> Move {
+        Move::Drop(self.columns.pop().expect("script ran out of moves"))
+    }
+
+    fn opponent_moved(&mut self, _game: &GameState, team: Team, mv: Move) {
+        self.log.borrow_mut().opponent_moves.push((team, mv));
+    }
+
+    fn game_ended(&mut self, _game: &GameState) {
+        self.log.borrow_mut().games_ended += 1;
+    }
+
+    fn move_undone(&mut self, _game: &GameState, team: Team, mv: Move) {
+        self.log.borrow_mut().undone.push((team, mv));
+    }
+
+    fn game_replaced(&mut self, _game: &GameState) {
+        self.log.borrow_mut().games_replaced += 1;
+    }
+}
+
+#[test]
+fn plays_to_the_end() -> Result<(), Error> {
+    let (first, first_log) = Scripted::new(&[0, 0, 0, 0]);
+    let (second, second_log) = Scripted::new(&[1, 1, 1]);
+    let mut game_match = Match::new(
+        GameState::default(),
+        vec![Box::new(first), Box::new(second)],
+    )?;
+    assert_eq!(game_match.play()?, GameOutcome::Won(Team::new(0)));
+    assert_eq!(game_match.game().history().len(), 7);
+
+    let first_log = first_log.borrow();
+    let second_log = second_log.borrow();
+    assert_eq!(
+        first_log.opponent_moves,
+        vec![(Team::new(1), Move::Drop(1)); 3]
+    );
+    assert_eq!(
+        second_log.opponent_moves,
+        vec![(Team::new(0), Move::Drop(0)); 4]
+    );
+    assert_eq!(first_log.games_ended, 1);
+    assert_eq!(second_log.games_ended, 1);
+    Ok(())
+}
+
+#[test]
+fn wrong_number_of_players() {
+    let (player, _) = Scripted::new(&[]);
+    let result = Match::new(GameState::default(), vec![Box::new(player)]);
+    assert_eq!(result.err(), Some(Error::WrongNumberOfPlayers));
+}
+
+#[test]
+fn manual_moves_notify_players() -> Result<(), Error> {
+    let (first, first_log) = Scripted::new(&[]);
+    let (second, second_log) = Scripted::new(&[]);
+    let mut game_match = Match::new(
+        GameState::default(),
+        vec![Box::new(first), Box::new(second)],
+    )?;
+    game_match.drop_chip(3)?;
+    game_match.undo()?;
+    game_match.redo()?;
+    assert_eq!(
+        second_log.borrow().opponent_moves,
+        vec![(Team::new(0), Move::Drop(3)); 2]
+    );
+    assert!(first_log.borrow().opponent_moves.is_empty());
+    assert_eq!(game_match.drop_chip(7), Err(Error::OutOfBounds));
+    Ok(())
+}
+
+#[test]
+fn undo_and_new_games_notify_players() -> Result<(), Error> {
+    let (first, first_log) = Scripted::new(&[]);
+    let (second, second_log) = Scripted::new(&[]);
+    let mut game_match = Match::new(
+        GameState::default(),
+        vec![Box::new(first), Box::new(second)],
+    )?;
+    game_match.drop_chip(3)?;
+    game_match.drop_chip(4)?;
+    game_match.undo()?;
+    game_match.undo()?;
+    assert_eq!(game_match.undo(), Err(Error::NothingToUndo));
+    let undone = vec![(Team::new(1), Move::Drop(4)), (Team::new(0), Move::Drop(3))];
+    assert_eq!(first_log.borrow().undone, undone);
+    assert_eq!(second_log.borrow().undone, undone);
+
+    game_match.set_game(GameState::default())?;
+    assert_eq!(first_log.borrow().games_replaced, 1);
+    assert_eq!(second_log.borrow().games_replaced, 1);
+    let three_teams = GameState::new(Team::new(0), 3, 6, 7, 4)?;
+    assert_eq!(
+        game_match.set_game(three_teams),
+        Err(Error::WrongNumberOfPlayers)
+    );
+    assert_eq!(first_log.borrow().games_replaced, 1);
+    Ok(())
+}
+
+#[test]
+fn observers_see_every_event() -> Result<(), Error> {
+    let (first, _) = Scripted::new(&[0, 0, 0, 0]);
+    let (second, _) = Scripted::new(&[1, 1, 1]);
+    let mut game_match = Match::new(
+        GameState::default(),
+        vec![Box::new(first), Box::new(second)],
+    )?;
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let seen = Rc::clone(&events);
+    game_match.subscribe(Box::new(move |_: &GameState, event: &GameEvent| {
+        seen.borrow_mut().push(event.clone())
+    }));
+    game_match.play()?;
+    game_match.undo()?;
+
+    let events = events.borrow();
+    let dropped = events
+        .iter()
+        .filter(|event| matches!(event, GameEvent::ChipDropped { .. }))
+        .count();
+    assert_eq!(dropped, 7);
+    assert!(events.contains(&GameEvent::GameWon {
+        team: Team::new(0),
+        line: WinningLine {
+            team: Team::new(0),
+            direction: Direction::Vertical,
+            cells: vec![(0, 0), (1, 0), (2, 0), (3, 0)],
+        },
+    }));
+    assert_eq!(
+        events[events.len() - 2],
+        GameEvent::MoveUndone {
+            team: Team::new(0),
+            row: 3,
+            column: 0
+        }
+    );
+    Ok(())
+}
@@ -0,0 +1,135 @@
+extern crate connect_four;
+
+use connect_four::game::*;
+use connect_four::render::*;
+
+const ESC: char = 27 as char;
+
+#[test]
+fn plain_board() -> Result<(), MoveStringError> {
+    let game = GameState::from_moves("12", &GameConfig::default())?;
+    let expected = "\
+0 1 2 3 4 5 6
+-------------
+_ _ _ _ _ _ _
+_ _ _ _ _ _ _
+_ _ _ _ _ _ _
+_ _ _ _ _ _ _
+_ _ _ _ _ _ _
+0 1 _ _ _ _ _";
+    assert_eq!(Renderer::Plain.render(&game, &[]), expected);
+    let highlighted = Renderer::Plain.render(&game, &[(0, 1)]);
+    assert!(highlighted.ends_with(&format!("0 {}[7m1{}[0m _ _ _ _ _", ESC, ESC)));
+    Ok(())
+}
+
+#[test]
+fn colored_board() -> Result<(), MoveStringError> {
+    let game = GameState::from_moves("12", &GameConfig::default())?;
+    let board = Renderer::Color(Palette::Standard).render(&game, &[]);
+    let bottom_row = board.lines().nth(7).unwrap();
+    assert!(bottom_row.contains('●'));
+    assert!(bottom_row.contains('◉'));
+    assert!(!bottom_row.contains('_'));
+    assert_eq!(
+        board.lines().last(),
+        Some(&*format!(
+            "{}[38;5;196m●{}[0m Team 0  {}[38;5;226m●{}[0m Team 1",
+            ESC, ESC, ESC, ESC
+        ))
+    );
+    Ok(())
+}
+
+#[test]
+fn palettes_differ() -> Result<(), MoveStringError> {
+    let game = GameState::from_moves("1234", &GameConfig::default())?;
+    let standard = Renderer::Color(Palette::Standard).render(&game, &[]);
+    let high_contrast = Renderer::Color(Palette::HighContrast).render(&game, &[]);
+    assert_ne!(standard, high_contrast);
+    assert!(!high_contrast.contains('●'));
+    Ok(())
+}
+
+#[test]
+fn custom_team_info() -> Result<(), Error> {
+    let mut game = GameState::default();
+    let info = TeamInfo {
+        name: "Reds".to_string(),
+        glyph: "R".to_string(),
+        color: Some(160),
+    };
+    game.set_team_info(Team::new(0), info)?;
+    game.drop_chip(Team::new(0), 0)?;
+    let plain = Renderer::Plain.render(&game, &[]);
+    assert!(plain.ends_with("R _ _ _ _ _ _"));
+    let colored = Renderer::Color(Palette::Standard).render(&game, &[]);
+    assert!(colored
+        .lines()
+        .last()
+        .unwrap()
+        .contains(&format!("{}[38;5;160m●{}[0m Reds", ESC, ESC)));
+    let high_contrast = Renderer::Color(Palette::HighContrast).render(&game, &[]);
+    assert!(high_contrast.contains('R'));
+    Ok(())
+}
+
+#[test]
+fn wide_columns_line_up() -> Result<(), Error> {
+    let mut game = GameState::new(Team::new(0), 40, 2, 17, 2)?;
+    for column in 0..17 {
+        let team = game.cur_turn();
+        game.drop_chip(team, column)?;
+    }
+    let board = Renderer::Plain.render(&game, &[]);
+    let lines: Vec<_> = board.lines().collect();
+    assert_eq!(
+        lines[0],
+        " 0  1  2  3  4  5  6  7  8  9  A  B  C  D  E  F 10"
+    );
+    assert_eq!(lines[1].len(), lines[0].len());
+    assert_eq!(
+        lines[3],
+        " 0  1  2  3  4  5  6  7  8  9  a  b  c  d  e  f  g"
+    );
+    assert_eq!(
+        lines[2],
+        "__ __ __ __ __ __ __ __ __ __ __ __ __ __ __ __ __"
+    );
+
+    let colored = Renderer::Color(Palette::Standard).render(&game, &[]);
+    assert!(!colored.contains('●'));
+    assert!(colored.contains(" g"));
+    Ok(())
+}
+
+#[test]
+fn cylinder_marks_the_wrap() -> Result<(), Error> {
+    let game = GameState::from_config(&GameConfig {
+        topology: Topology::Cylinder,
+        ..GameConfig::default()
+    })?;
+    let board = Renderer::Plain.render(&game, &[]);
+    assert_eq!(board.lines().nth(1), Some("<----------->"));
+    Ok(())
+}
+
+#[test]
+fn free_placement_numbers_rows() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        num_rows: 3,
+        num_columns: 3,
+        winning_length: 3,
+        placement: Placement::Free,
+        ..GameConfig::default()
+    })?;
+    game.place_chip(Team::new(0), 2, 1)?;
+    let expected = "\
+0 1 2
+-----
+_ 0 _ 2
+_ _ _ 1
+_ _ _ 0";
+    assert_eq!(Renderer::Plain.render(&game, &[]), expected);
+    Ok(())
+}
@@ -0,0 +1,707 @@
+extern crate connect_four;
+
+use connect_four::game::*;
+
+/// Team 0 ends with six in a row along the bottom.
+const OVERLINE_MOVES: &str = "11223355664";
+
+/// The usual rules on a `num_rows` by `num_columns` board, for each test
+/// to change the rule it covers.
+fn board(num_rows: usize, num_columns: usize, winning_length: usize) -> GameConfig {
+    GameConfig {
+        num_rows,
+        num_columns,
+        winning_length,
+        ..GameConfig::default()
+    }
+}
+
+/// Makes each move for the team whose turn it is.
+fn play(game: &mut GameState, moves: &[Move]) -> Result<(), Error> {
+    for &mv in moves {
+        let team = game.cur_turn();
+        game.play_move(team, mv)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn pop_moves_the_column_down() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        pop_out: true,
+        ..board(6, 7, 4)
+    })?;
+    play(&mut game, &[Move::Drop(0), Move::Drop(0), Move::Drop(1)])?;
+    let events = game.pop_chip(Team::new(1), 1);
+    assert_eq!(events, Err(Error::CannotPop));
+    game.drop_chip(Team::new(1), 0)?;
+    let events = game.pop_chip(Team::new(0), 0)?;
+    assert_eq!(
+        events,
+        vec![
+            GameEvent::ChipPopped {
+                team: Team::new(0),
+                column: 0
+            },
+            GameEvent::TurnChanged { team: Team::new(1) },
+        ]
+    );
+    assert_eq!(game.cell(0, 0), Some(Team::new(1)));
+    assert_eq!(game.cell(1, 0), Some(Team::new(1)));
+    assert_eq!(game.cell(2, 0), None);
+    assert_eq!(game.history().last().unwrap().kind, MoveKind::Pop);
+    assert_eq!(game.moves_played(), 5);
+    assert_eq!(game.legal_pops(), vec![0]);
+    assert_eq!(
+        game.available_moves(),
+        (0..7)
+            .map(Move::Drop)
+            .chain(Some(Move::Pop(0)))
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_pops() -> Result<(), Error> {
+    let mut game = GameState::default();
+    game.drop_chip(Team::new(0), 0)?;
+    game.drop_chip(Team::new(1), 1)?;
+    assert_eq!(game.pop_chip(Team::new(0), 0), Err(Error::PopOutNotAllowed));
+    assert!(game.legal_pops().is_empty());
+
+    let mut game = GameState::from_config(&GameConfig {
+        pop_out: true,
+        ..board(6, 7, 4)
+    })?;
+    game.drop_chip(Team::new(0), 0)?;
+    assert_eq!(game.pop_chip(Team::new(1), 0), Err(Error::CannotPop));
+    assert_eq!(game.pop_chip(Team::new(1), 1), Err(Error::CannotPop));
+    assert_eq!(game.pop_chip(Team::new(1), 7), Err(Error::OutOfBounds));
+    assert_eq!(game.pop_chip(Team::new(0), 0), Err(Error::NotThatTeamsTurn));
+    Ok(())
+}
+
+#[test]
+fn popper_wins_simultaneous_lines() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        pop_out: true,
+        ..board(4, 4, 3)
+    })?;
+    // Popping column 0 leaves a row of team 1 under a row of team 0.
+    play(
+        &mut game,
+        &[
+            Move::Drop(0),
+            Move::Drop(0),
+            Move::Drop(0),
+            Move::Drop(1),
+            Move::Drop(1),
+            Move::Drop(2),
+            Move::Drop(2),
+            Move::Drop(1),
+        ],
+    )?;
+    assert!(!game.game_over());
+    game.pop_chip(Team::new(0), 0)?;
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    assert!(!game.winning_lines().is_empty());
+    assert!(game
+        .winning_lines()
+        .iter()
+        .all(|line| line.team == Team::new(0)));
+    Ok(())
+}
+
+#[test]
+fn pop_can_win_for_the_opponent() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        pop_out: true,
+        ..board(4, 4, 3)
+    })?;
+    // Popping column 0 only completes team 1's bottom row.
+    play(
+        &mut game,
+        &[
+            Move::Drop(0),
+            Move::Drop(0),
+            Move::Drop(3),
+            Move::Drop(1),
+            Move::Drop(3),
+            Move::Drop(2),
+        ],
+    )?;
+    game.pop_chip(Team::new(0), 0)?;
+    assert_eq!(game.who_won(), Some(Team::new(1)));
+    Ok(())
+}
+
+#[test]
+fn full_board_with_pops_left() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        pop_out: true,
+        ..board(1, 4, 3)
+    })?;
+    play(
+        &mut game,
+        &[Move::Drop(0), Move::Drop(1), Move::Drop(2), Move::Drop(3)],
+    )?;
+    assert!(game.is_full());
+    assert!(!game.game_over());
+    assert_eq!(game.available_moves(), vec![Move::Pop(0), Move::Pop(2)]);
+
+    let mut game = GameState::from_config(&GameConfig {
+        num_teams: 3,
+        pop_out: true,
+        ..board(1, 2, 2)
+    })?;
+    game.drop_chip(Team::new(0), 0)?;
+    let events = game.drop_chip(Team::new(1), 1)?;
+    assert_eq!(events.last(), Some(&GameEvent::GameDrawn));
+    assert_eq!(game.outcome(), GameOutcome::Draw);
+    Ok(())
+}
+
+#[test]
+fn repeated_position_is_a_draw() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        pop_out: true,
+        ..board(1, 4, 3)
+    })?;
+    let round = [Move::Drop(0), Move::Drop(1), Move::Pop(0), Move::Pop(1)];
+    play(&mut game, &round)?;
+    assert!(!game.game_over());
+    play(&mut game, &round[..3])?;
+    let events = game.pop_chip(Team::new(1), 1)?;
+    assert_eq!(events.last(), Some(&GameEvent::GameDrawn));
+    assert_eq!(game.outcome(), GameOutcome::Draw);
+
+    game.undo()?;
+    assert!(!game.game_over());
+    game.redo()?;
+    assert_eq!(game.outcome(), GameOutcome::Draw);
+    Ok(())
+}
+
+#[test]
+fn undo_and_redo_pop() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        pop_out: true,
+        ..board(6, 7, 4)
+    })?;
+    play(&mut game, &[Move::Drop(2), Move::Drop(2), Move::Drop(4)])?;
+    game.drop_chip(Team::new(1), 4)?;
+    let before = game.to_string_arr();
+    game.pop_chip(Team::new(0), 2)?;
+    let after = game.to_string_arr();
+    assert_ne!(before, after);
+
+    let events = game.undo()?;
+    assert_eq!(
+        events[0],
+        GameEvent::MoveUndone {
+            team: Team::new(0),
+            row: 0,
+            column: 2
+        }
+    );
+    assert_eq!(game.to_string_arr(), before);
+    assert_eq!(game.cur_turn(), Team::new(0));
+    game.redo()?;
+    assert_eq!(game.to_string_arr(), after);
+    assert_eq!(game.history().last().unwrap().to_move(), Move::Pop(2));
+    Ok(())
+}
+
+#[test]
+fn pops_are_not_move_strings() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        pop_out: true,
+        ..board(6, 7, 4)
+    })?;
+    play(&mut game, &[Move::Drop(2), Move::Drop(3)])?;
+    assert_eq!(game.to_move_string(), Ok("34".to_string()));
+    game.pop_chip(Team::new(0), 2)?;
+    assert_eq!(game.to_move_string(), Err(Error::UnsupportedMove));
+    Ok(())
+}
+
+#[test]
+fn cylinder_wraps_horizontally() -> Result<(), MoveStringError> {
+    let config = GameConfig {
+        topology: Topology::Cylinder,
+        ..GameConfig::default()
+    };
+    let game = GameState::from_moves("6474142", &config)?;
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    assert_eq!(game.winning_lines()[0].direction, Direction::Horizontal);
+    assert_eq!(
+        game.winning_lines()[0].cells,
+        vec![(0, 5), (0, 6), (0, 0), (0, 1)]
+    );
+
+    let flat = GameState::from_moves("6474142", &GameConfig::default())?;
+    assert!(!flat.game_over());
+    Ok(())
+}
+
+#[test]
+fn cylinder_wraps_diagonally() -> Result<(), MoveStringError> {
+    let config = GameConfig {
+        topology: Topology::Cylinder,
+        ..board(4, 4, 3)
+    };
+    let game = GameState::from_moves("4112322", &config)?;
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    assert_eq!(game.winning_lines()[0].direction, Direction::UpRight);
+    assert_eq!(game.winning_lines()[0].cells, vec![(0, 3), (1, 0), (2, 1)]);
+
+    let flat = GameState::from_moves("4112322", &board(4, 4, 3))?;
+    assert!(!flat.game_over());
+    Ok(())
+}
+
+#[test]
+fn cylinder_full_row_counts_each_chip_once() -> Result<(), MoveStringError> {
+    let config = GameConfig {
+        num_teams: 1,
+        topology: Topology::Cylinder,
+        ..board(1, 4, 4)
+    };
+    let game = GameState::from_moves("1423", &config)?;
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    assert_eq!(game.winning_lines().len(), 1);
+    assert_eq!(
+        game.winning_lines()[0].cells,
+        vec![(0, 2), (0, 3), (0, 0), (0, 1)]
+    );
+    Ok(())
+}
+
+#[test]
+fn tic_tac_toe_win() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        placement: Placement::Free,
+        ..board(3, 3, 3)
+    })?;
+    play(
+        &mut game,
+        &[
+            Move::Place(0, 0),
+            Move::Place(0, 1),
+            Move::Place(1, 1),
+            Move::Place(0, 2),
+        ],
+    )?;
+    assert_eq!(game.cell(1, 1), Some(Team::new(0)));
+    assert_eq!(game.cell(1, 0), None);
+    let events = game.place_chip(Team::new(0), 2, 2)?;
+    assert_eq!(
+        events[0],
+        GameEvent::ChipPlaced {
+            team: Team::new(0),
+            row: 2,
+            column: 2
+        }
+    );
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    assert_eq!(game.winning_lines()[0].direction, Direction::UpRight);
+    assert_eq!(game.winning_lines()[0].cells, vec![(0, 0), (1, 1), (2, 2)]);
+    Ok(())
+}
+
+#[test]
+fn tic_tac_toe_draw() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        placement: Placement::Free,
+        ..board(3, 3, 3)
+    })?;
+    play(
+        &mut game,
+        &[
+            Move::Place(0, 0),
+            Move::Place(0, 1),
+            Move::Place(0, 2),
+            Move::Place(1, 1),
+            Move::Place(1, 0),
+            Move::Place(1, 2),
+            Move::Place(2, 1),
+            Move::Place(2, 0),
+        ],
+    )?;
+    assert!(!game.game_over());
+    assert_eq!(game.legal_places(), vec![(2, 2)]);
+    let events = game.place_chip(Team::new(0), 2, 2)?;
+    assert_eq!(events.last(), Some(&GameEvent::GameDrawn));
+    assert_eq!(game.outcome(), GameOutcome::Draw);
+    assert!(game.legal_places().is_empty());
+    Ok(())
+}
+
+#[test]
+fn full_top_row_is_not_a_full_board() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        placement: Placement::Free,
+        ..board(3, 3, 3)
+    })?;
+    play(
+        &mut game,
+        &[
+            Move::Place(2, 0),
+            Move::Place(2, 1),
+            Move::Place(0, 0),
+            Move::Place(0, 2),
+            Move::Place(2, 2),
+        ],
+    )?;
+    assert!(!game.is_full());
+    assert!(!game.game_over());
+    assert_eq!(game.moves_played(), 5);
+    assert_eq!(game.legal_places(), vec![(0, 1), (1, 0), (1, 1), (1, 2)]);
+
+    play(
+        &mut game,
+        &[Move::Place(1, 1), Move::Place(0, 1), Move::Place(1, 0)],
+    )?;
+    assert!(!game.game_over());
+    let events = game.place_chip(Team::new(0), 1, 2)?;
+    assert!(game.is_full());
+    assert_eq!(events.last(), Some(&GameEvent::GameDrawn));
+    Ok(())
+}
+
+#[test]
+fn gomoku_line_in_mid_air() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        placement: Placement::Free,
+        ..board(15, 15, 5)
+    })?;
+    for column in 3..7 {
+        play(&mut game, &[Move::Place(7, column), Move::Place(8, column)])?;
+    }
+    assert!(!game.game_over());
+    game.place_chip(Team::new(0), 7, 7)?;
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    assert_eq!(game.winning_lines()[0].direction, Direction::Horizontal);
+    Ok(())
+}
+
+#[test]
+fn invalid_places() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        placement: Placement::Free,
+        ..board(3, 3, 3)
+    })?;
+    game.place_chip(Team::new(0), 1, 1)?;
+    assert_eq!(
+        game.place_chip(Team::new(1), 1, 1),
+        Err(Error::CellOccupied)
+    );
+    assert_eq!(game.place_chip(Team::new(1), 3, 0), Err(Error::OutOfBounds));
+    assert_eq!(game.place_chip(Team::new(1), 0, 3), Err(Error::OutOfBounds));
+    assert_eq!(game.drop_chip(Team::new(1), 0), Err(Error::DropNotAllowed));
+    assert!(game.legal_moves().is_empty());
+    assert_eq!(game.legal_places().len(), 8);
+    assert!(game
+        .available_moves()
+        .iter()
+        .all(|mv| matches!(mv, Move::Place(_, _))));
+
+    let mut gravity = GameState::default();
+    assert_eq!(
+        gravity.place_chip(Team::new(0), 0, 0),
+        Err(Error::PlaceNotAllowed)
+    );
+    assert!(gravity.legal_places().is_empty());
+    Ok(())
+}
+
+#[test]
+fn free_placement_is_not_pop_out() {
+    let config = GameConfig {
+        placement: Placement::Free,
+        pop_out: true,
+        ..GameConfig::default()
+    };
+    assert_eq!(
+        GameState::from_config(&config).err(),
+        Some(Error::IncompatibleRules)
+    );
+}
+
+#[test]
+fn undo_and_redo_place() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        placement: Placement::Free,
+        ..board(3, 3, 3)
+    })?;
+    play(&mut game, &[Move::Place(2, 0), Move::Place(1, 2)])?;
+    game.undo()?;
+    assert_eq!(game.cell(1, 2), None);
+    assert_eq!(game.cur_turn(), Team::new(1));
+    game.redo()?;
+    assert_eq!(game.cell(1, 2), Some(Team::new(1)));
+    assert_eq!(game.history()[1].to_move(), Move::Place(1, 2));
+    assert_eq!(game.to_move_string(), Err(Error::UnsupportedMove));
+    Ok(())
+}
+
+#[test]
+fn at_least_counts_overlines() -> Result<(), MoveStringError> {
+    let config = GameConfig {
+        win_rule: WinRule::AtLeast,
+        ..GameConfig::default()
+    };
+    let game = GameState::from_moves(OVERLINE_MOVES, &config)?;
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    assert_eq!(game.winning_lines()[0].cells.len(), 6);
+    Ok(())
+}
+
+#[test]
+fn exactly_ignores_overlines() -> Result<(), MoveStringError> {
+    let config = GameConfig {
+        win_rule: WinRule::Exactly,
+        ..GameConfig::default()
+    };
+    let game = GameState::from_moves(OVERLINE_MOVES, &config)?;
+    assert!(!game.game_over());
+    assert!(game.winning_lines().is_empty());
+
+    let game = GameState::from_moves("1212121", &config)?;
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    Ok(())
+}
+
+#[test]
+fn overline_loses() -> Result<(), Error> {
+    let config = GameConfig {
+        win_rule: WinRule::OverlineLoses,
+        ..GameConfig::default()
+    };
+    let mut game =
+        GameState::from_moves(&OVERLINE_MOVES[..OVERLINE_MOVES.len() - 1], &config).unwrap();
+    let events = game.drop_chip(Team::new(0), 3)?;
+    assert_eq!(game.who_won(), Some(Team::new(1)));
+    let line = &game.winning_lines()[0];
+    assert_eq!(line.team, Team::new(0));
+    assert_eq!(line.cells.len(), 6);
+    assert_eq!(
+        events.last(),
+        Some(&GameEvent::GameWon {
+            team: Team::new(1),
+            line: line.clone()
+        })
+    );
+
+    game.undo()?;
+    assert!(!game.game_over());
+    assert!(game.winning_lines().is_empty());
+    Ok(())
+}
+
+#[test]
+fn exact_line_beats_overline() -> Result<(), Error> {
+    for &win_rule in &[WinRule::Exactly, WinRule::OverlineLoses] {
+        exact_line_beats_overline_under(win_rule)?;
+    }
+    Ok(())
+}
+
+fn exact_line_beats_overline_under(win_rule: WinRule) -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        placement: Placement::Free,
+        win_rule,
+        ..board(7, 7, 4)
+    })?;
+    play(
+        &mut game,
+        &[
+            Move::Place(0, 0),
+            Move::Place(6, 0),
+            Move::Place(0, 1),
+            Move::Place(6, 1),
+            Move::Place(0, 2),
+            Move::Place(6, 2),
+            Move::Place(0, 4),
+            Move::Place(6, 4),
+            Move::Place(0, 5),
+            Move::Place(6, 5),
+            Move::Place(1, 3),
+            Move::Place(6, 6),
+            Move::Place(2, 3),
+            Move::Place(4, 0),
+            Move::Place(3, 3),
+            Move::Place(4, 6),
+        ],
+    )?;
+    assert!(!game.game_over());
+    // Completes an overline along the bottom and exactly four up column 3
+    game.place_chip(Team::new(0), 0, 3)?;
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    assert_eq!(game.winning_lines().len(), 1);
+    assert_eq!(game.winning_lines()[0].direction, Direction::Vertical);
+    Ok(())
+}
+
+#[test]
+fn overline_loses_needs_two_teams() {
+    let config = GameConfig {
+        num_teams: 3,
+        win_rule: WinRule::OverlineLoses,
+        ..GameConfig::default()
+    };
+    assert_eq!(
+        GameState::from_config(&config).err(),
+        Some(Error::IncompatibleRules)
+    );
+    let config = GameConfig {
+        num_teams: 3,
+        win_rule: WinRule::Exactly,
+        ..GameConfig::default()
+    };
+    assert!(GameState::from_config(&config).is_ok());
+}
+
+#[test]
+fn lines_do_not_end_the_game() -> Result<(), MoveStringError> {
+    let config = GameConfig {
+        scoring: ScoringMode::CountLines,
+        ..GameConfig::default()
+    };
+    let game = GameState::from_moves("1212121", &config)?;
+    assert!(!game.game_over());
+    assert!(game.winning_lines().is_empty());
+    assert_eq!(game.scores(), vec![(Team::new(0), 1), (Team::new(1), 0)]);
+
+    let game = GameState::from_moves("1212121", &GameConfig::default())?;
+    assert!(game.game_over());
+    assert_eq!(game.scores(), vec![(Team::new(0), 1), (Team::new(1), 0)]);
+    Ok(())
+}
+
+#[test]
+fn most_lines_wins_when_full() -> Result<(), Error> {
+    let config = GameConfig {
+        scoring: ScoringMode::CountLines,
+        ..board(1, 5, 2)
+    };
+    let mut game = GameState::from_moves_zero_based("0314", &config).unwrap();
+    assert!(!game.game_over());
+    let events = game.drop_chip(Team::new(0), 2)?;
+    assert!(game.is_full());
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    // Three in a row holds two overlapping lines of two
+    assert_eq!(game.scores(), vec![(Team::new(0), 2), (Team::new(1), 1)]);
+    assert_eq!(game.winning_lines().len(), 2);
+    assert_eq!(game.winning_lines()[1].cells, vec![(0, 1), (0, 2)]);
+    assert_eq!(
+        events.last(),
+        Some(&GameEvent::GameWon {
+            team: Team::new(0),
+            line: game.winning_lines()[1].clone()
+        })
+    );
+
+    game.undo()?;
+    assert!(!game.game_over());
+    assert!(game.winning_lines().is_empty());
+    game.redo()?;
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    Ok(())
+}
+
+#[test]
+fn free_placement_plays_every_cell() -> Result<(), Error> {
+    let mut game = GameState::from_config(&GameConfig {
+        placement: Placement::Free,
+        scoring: ScoringMode::CountLines,
+        ..board(3, 3, 3)
+    })?;
+    let cells = [
+        (2, 0),
+        (2, 1),
+        (2, 2),
+        (1, 2),
+        (1, 0),
+        (0, 1),
+        (1, 1),
+        (0, 2),
+        (0, 0),
+    ];
+    for (i, &(row, column)) in cells.iter().enumerate() {
+        assert!(!game.game_over(), "ended after {} chips", i);
+        let team = game.cur_turn();
+        game.place_chip(team, row, column)?;
+    }
+    assert!(game.is_full());
+    assert_eq!(game.scores(), vec![(Team::new(0), 2), (Team::new(1), 0)]);
+    assert_eq!(game.who_won(), Some(Team::new(0)));
+    assert_eq!(game.winning_lines().len(), 2);
+    Ok(())
+}
+
+#[test]
+fn tied_scores_are_a_draw() -> Result<(), MoveStringError> {
+    let config = GameConfig {
+        scoring: ScoringMode::CountLines,
+        ..board(1, 4, 2)
+    };
+    let game = GameState::from_moves_zero_based("0213", &config)?;
+    assert_eq!(game.scores(), vec![(Team::new(0), 1), (Team::new(1), 1)]);
+    assert_eq!(game.outcome(), GameOutcome::Draw);
+
+    let game = GameState::from_moves_zero_based("0123", &config)?;
+    assert_eq!(game.scores(), vec![(Team::new(0), 0), (Team::new(1), 0)]);
+    assert_eq!(game.outcome(), GameOutcome::Draw);
+    Ok(())
+}
+
+#[test]
+fn counts_lines_around_a_cylinder() -> Result<(), MoveStringError> {
+    let flat_config = GameConfig {
+        scoring: ScoringMode::CountLines,
+        ..board(1, 4, 2)
+    };
+    let flat = GameState::from_moves_zero_based("0132", &flat_config)?;
+    assert_eq!(flat.scores(), vec![(Team::new(0), 0), (Team::new(1), 1)]);
+    assert_eq!(flat.who_won(), Some(Team::new(1)));
+
+    let config = GameConfig {
+        topology: Topology::Cylinder,
+        ..flat_config
+    };
+    let cylinder = GameState::from_moves_zero_based("0132", &config)?;
+    assert_eq!(
+        cylinder.scores(),
+        vec![(Team::new(0), 1), (Team::new(1), 1)]
+    );
+    assert_eq!(cylinder.outcome(), GameOutcome::Draw);
+    Ok(())
+}
+
+#[test]
+fn count_lines_needs_compatible_rules() {
+    let pop_out = GameConfig {
+        pop_out: true,
+        scoring: ScoringMode::CountLines,
+        ..board(6, 7, 4)
+    };
+    let exactly = GameConfig {
+        win_rule: WinRule::Exactly,
+        scoring: ScoringMode::CountLines,
+        ..board(6, 7, 4)
+    };
+    for config in &[pop_out, exactly] {
+        assert_eq!(
+            GameState::from_config(config).err(),
+            Some(Error::IncompatibleRules)
+        );
+    }
+    let free = GameConfig {
+        placement: Placement::Free,
+        scoring: ScoringMode::CountLines,
+        ..board(6, 7, 4)
+    };
+    assert!(GameState::from_config(&free).is_ok());
+}
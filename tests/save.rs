@@ -0,0 +1,220 @@
+extern crate connect_four;
+
+use connect_four::bitboard::BitBoard;
+use connect_four::game::*;
+use connect_four::save::*;
+use std::env;
+
+fn sample_game() -> Result<GameState, Error> {
+    let mut game = GameState::new(Team::new(1), 3, 8, 9, 5)?;
+    for &column in &[4, 4, 3, 0, 8, 8, 2] {
+        let team = game.cur_turn();
+        game.drop_chip(team, column)?;
+    }
+    Ok(game)
+}
+
+#[test]
+fn save_string_format() -> Result<(), Error> {
+    let game = sample_game()?;
+    assert_eq!(
+        to_save_string(&game),
+        "# connect-four save file\n\
+         teams 3\n\
+         rows 8\n\
+         columns 9\n\
+         winning-length 5\n\
+         first-turn 1\n\
+         current-turn 2\n\
+         moves 4 4 3 0 8 8 2\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn round_trip_string() -> Result<(), Error> {
+    let game = sample_game()?;
+    let loaded = from_save_string(&to_save_string(&game)).unwrap();
+    assert_eq!(loaded.to_string_arr(), game.to_string_arr());
+    assert_eq!(loaded.history(), game.history());
+    assert_eq!(loaded.cur_turn(), game.cur_turn());
+    assert_eq!(loaded.first_turn(), game.first_turn());
+    assert_eq!(loaded.winning_length(), game.winning_length());
+    Ok(())
+}
+
+#[test]
+fn round_trip_file() -> Result<(), Error> {
+    let game = sample_game()?;
+    let path = env::temp_dir().join("connect-four-round-trip-file.txt");
+    save(&game, &path).unwrap();
+    let loaded = load(&path).unwrap();
+    assert_eq!(loaded.history(), game.history());
+    Ok(())
+}
+
+#[test]
+fn empty_move_list() {
+    let game = from_save_string(
+        "teams 2\nrows 6\ncolumns 7\nwinning-length 4\nfirst-turn 0\ncurrent-turn 0\nmoves\n",
+    )
+    .unwrap();
+    assert!(game.history().is_empty());
+}
+
+#[test]
+fn rejects_invalid_saves() {
+    let valid =
+        "teams 2\nrows 6\ncolumns 7\nwinning-length 4\nfirst-turn 0\ncurrent-turn 1\nmoves 3\n";
+    assert!(from_save_string(valid).is_ok());
+
+    let unknown = valid.replace("rows", "height");
+    match from_save_string(&unknown) {
+        Err(SaveError::InvalidLine(2)) => (),
+        other => panic!("unexpected result {:?}", other.err()),
+    }
+    let bad_value = valid.replace("columns 7", "columns seven");
+    match from_save_string(&bad_value) {
+        Err(SaveError::InvalidValue(3)) => (),
+        other => panic!("unexpected result {:?}", other.err()),
+    }
+    let missing = valid.replace("winning-length 4\n", "");
+    match from_save_string(&missing) {
+        Err(SaveError::MissingField("winning-length")) => (),
+        other => panic!("unexpected result {:?}", other.err()),
+    }
+    let bad_move = valid.replace("moves 3", "moves 3 9");
+    match from_save_string(&bad_move) {
+        Err(SaveError::InvalidMove(1, Error::OutOfBounds)) => (),
+        other => panic!("unexpected result {:?}", other.err()),
+    }
+    let wrong_turn = valid.replace("current-turn 1", "current-turn 0");
+    match from_save_string(&wrong_turn) {
+        Err(SaveError::TurnMismatch) => (),
+        other => panic!("unexpected result {:?}", other.err()),
+    }
+    let invalid_game = valid.replace("teams 2", "teams 0");
+    match from_save_string(&invalid_game) {
+        Err(SaveError::Game(Error::NoTeams)) => (),
+        other => panic!("unexpected result {:?}", other.err()),
+    }
+}
+
+#[test]
+fn round_trip_starting_chips() -> Result<(), Error> {
+    let mut board = BitBoard::new(Team::new(0), 6, 7, 4)?;
+    for &column in &[3, 3, 4] {
+        board.play(column);
+    }
+    let mut game = board.to_game_state();
+    game.drop_chip(Team::new(1), 2)?;
+    let saved = to_save_string(&game);
+    assert!(saved.contains("start 0 - - - 0 0 - -\nstart 1 - - - 1 - - -\nmoves 2\n"));
+    let loaded = from_save_string(&saved).unwrap();
+    assert_eq!(loaded.to_string_arr(), game.to_string_arr());
+    assert_eq!(loaded.history(), game.history());
+    assert_eq!(loaded.moves_played(), 4);
+
+    let out_of_turn = saved.replace("start 1 - - - 1", "start 1 - - - 0");
+    match from_save_string(&out_of_turn) {
+        Err(SaveError::Game(Error::InconsistentState)) => (),
+        other => panic!("unexpected result {:?}", other.err()),
+    }
+    let short_row = saved.replace("start 1 - - - 1 - - -", "start 1 - - - 1");
+    match from_save_string(&short_row) {
+        Err(SaveError::InvalidValue(_)) => (),
+        other => panic!("unexpected result {:?}", other.err()),
+    }
+    Ok(())
+}
+
+#[test]
+fn round_trip_team_info() -> Result<(), Error> {
+    let mut game = sample_game()?;
+    let info = TeamInfo {
+        name: "The Red Team".to_string(),
+        glyph: "R".to_string(),
+        color: Some(196),
+    };
+    game.set_team_info(Team::new(2), info.clone())?;
+    let saved = to_save_string(&game);
+    assert!(saved.contains("\nteam 2 R 196 The Red Team\n"));
+    assert!(!saved.contains("\nteam 0"));
+    let loaded = from_save_string(&saved).unwrap();
+    assert_eq!(loaded.team_info(Team::new(2)), &info);
+    assert_eq!(loaded.to_string_arr(), game.to_string_arr());
+    Ok(())
+}
+
+/// Each rule that is not the default gets its own line, and its moves are
+/// written so that they can be replayed.
+#[test]
+fn round_trip_rules() -> Result<(), Error> {
+    let rules: &[(GameConfig, &[Move], &str, &str)] = &[
+        (
+            GameConfig {
+                pop_out: true,
+                ..GameConfig::default()
+            },
+            &[Move::Drop(3), Move::Drop(4), Move::Pop(3), Move::Drop(4)],
+            "pop-out yes",
+            "moves 3 4 p3 4",
+        ),
+        (
+            GameConfig {
+                topology: Topology::Cylinder,
+                ..GameConfig::default()
+            },
+            &[Move::Drop(6), Move::Drop(0)],
+            "topology cylinder",
+            "moves 6 0",
+        ),
+        (
+            GameConfig {
+                placement: Placement::Free,
+                ..GameConfig::default()
+            },
+            &[Move::Place(3, 4), Move::Place(5, 0)],
+            "placement free",
+            "moves 3,4 5,0",
+        ),
+        (
+            GameConfig {
+                win_rule: WinRule::OverlineLoses,
+                ..GameConfig::default()
+            },
+            &[Move::Drop(2), Move::Drop(2)],
+            "win-rule overline-loses",
+            "moves 2 2",
+        ),
+        (
+            GameConfig {
+                scoring: ScoringMode::CountLines,
+                ..GameConfig::default()
+            },
+            &[Move::Drop(0), Move::Drop(1), Move::Drop(0), Move::Drop(1)],
+            "scoring count-lines",
+            "moves 0 1 0 1",
+        ),
+    ];
+    let default_saved = to_save_string(&sample_game()?);
+    for &(config, moves, rule_line, moves_line) in rules {
+        let mut game = GameState::from_config(&config)?;
+        for &mv in moves {
+            let team = game.cur_turn();
+            game.play_move(team, mv)?;
+        }
+        let saved = to_save_string(&game);
+        assert!(saved.contains(&format!("\n{}\n", rule_line)), "{}", saved);
+        assert!(saved.ends_with(&format!("\n{}\n", moves_line)), "{}", saved);
+        let loaded = from_save_string(&saved).unwrap();
+        assert_eq!(loaded.config(), game.config());
+        assert_eq!(loaded.history(), game.history());
+        assert_eq!(loaded.to_string_arr(), game.to_string_arr());
+        assert_eq!(loaded.scores(), game.scores());
+
+        let key = rule_line.split(' ').next().unwrap();
+        assert!(!default_saved.contains(key), "{}", key);
+    }
+    Ok(())
+}
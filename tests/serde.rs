@@ -0,0 +1,249 @@
+#![cfg(feature = "serde")]
+
+extern crate connect_four;
+extern crate serde_json;
+
+use connect_four::bitboard::BitBoard;
+use connect_four::game::*;
+use serde_json::{json, Value};
+
+fn sample_game() -> Result<GameState, Error> {
+    let mut game = GameState::new(Team::new(1), 3, 6, 7, 4)?;
+    for &column in &[3, 3, 4, 2, 5, 0, 3] {
+        let team = game.cur_turn();
+        game.drop_chip(team, column)?;
+    }
+    game.undo()?;
+    game.undo()?;
+    let info = TeamInfo {
+        name: "Blue".to_string(),
+        glyph: "B".to_string(),
+        color: Some(33),
+    };
+    game.set_team_info(Team::new(2), info)?;
+    Ok(game)
+}
+
+fn assert_same_game(left: &GameState, right: &GameState) {
+    assert_eq!(left.to_string_arr(), right.to_string_arr());
+    assert_eq!(left.history(), right.history());
+    assert_eq!(left.first_turn(), right.first_turn());
+    assert_eq!(left.cur_turn(), right.cur_turn());
+    assert_eq!(left.outcome(), right.outcome());
+    assert_eq!(left.winning_lines(), right.winning_lines());
+    assert_eq!(left.config(), right.config());
+    for team in (0..left.num_teams()).map(Team::new) {
+        assert_eq!(left.team_info(team), right.team_info(team));
+    }
+}
+
+fn to_value(game: &GameState) -> Value {
+    serde_json::to_value(game).unwrap()
+}
+
+fn from_value(value: Value) -> Result<GameState, serde_json::Error> {
+    serde_json::from_value(value)
+}
+
+#[test]
+fn round_trip() -> Result<(), Error> {
+    let game = sample_game()?;
+    let json = serde_json::to_string(&game).unwrap();
+    let mut loaded: GameState = serde_json::from_str(&json).unwrap();
+    assert_same_game(&loaded, &game);
+
+    let mut game = game;
+    game.redo()?;
+    loaded.redo()?;
+    assert_same_game(&loaded, &game);
+    Ok(())
+}
+
+#[test]
+fn round_trip_finished_game() -> Result<(), MoveStringError> {
+    let game = GameState::from_moves("1212121", &GameConfig::default())?;
+    let loaded = from_value(to_value(&game)).unwrap();
+    assert_same_game(&loaded, &game);
+    assert_eq!(loaded.who_won(), Some(Team::new(0)));
+    Ok(())
+}
+
+#[test]
+fn round_trip_without_history() -> Result<(), MoveStringError> {
+    let game = GameState::from_moves("4455", &GameConfig::default())?;
+    let game = BitBoard::from_game_state(&game)
+        .map_err(MoveStringError::InvalidConfig)?
+        .to_game_state();
+    let loaded = from_value(to_value(&game)).unwrap();
+    assert_same_game(&loaded, &game);
+    Ok(())
+}
+
+/// Plays `moves` under each rule, then checks the game before and after
+/// redoing the last move.
+#[test]
+fn round_trip_rules() -> Result<(), Error> {
+    let drops = |columns: &[usize]| columns.iter().map(|&c| Move::Drop(c)).collect::<Vec<_>>();
+    let rules: &[(GameConfig, Vec<Move>, Option<Team>)] = &[
+        (
+            GameConfig {
+                pop_out: true,
+                ..GameConfig::default()
+            },
+            vec![
+                Move::Drop(3),
+                Move::Drop(4),
+                Move::Pop(3),
+                Move::Drop(3),
+                Move::Drop(4),
+                Move::Pop(3),
+            ],
+            None,
+        ),
+        (
+            GameConfig {
+                topology: Topology::Cylinder,
+                ..GameConfig::default()
+            },
+            drops(&[5, 3, 6, 3, 0, 3, 1]),
+            Some(Team::new(0)),
+        ),
+        (
+            GameConfig {
+                placement: Placement::Free,
+                ..GameConfig::default()
+            },
+            vec![Move::Place(3, 4), Move::Place(5, 0), Move::Place(0, 6)],
+            None,
+        ),
+        (
+            GameConfig {
+                win_rule: WinRule::OverlineLoses,
+                ..GameConfig::default()
+            },
+            drops(&[0, 0, 1, 1, 2, 2, 4, 4, 5, 5, 3]),
+            Some(Team::new(1)),
+        ),
+        (
+            GameConfig {
+                num_rows: 1,
+                num_columns: 5,
+                winning_length: 2,
+                scoring: ScoringMode::CountLines,
+                ..GameConfig::default()
+            },
+            drops(&[0, 3, 1, 4, 2]),
+            Some(Team::new(0)),
+        ),
+    ];
+    for (config, moves, winner) in rules {
+        let mut game = GameState::from_config(config)?;
+        for &mv in moves {
+            let team = game.cur_turn();
+            game.play_move(team, mv)?;
+        }
+        assert_eq!(game.who_won(), *winner);
+        assert_same_game(&from_value(to_value(&game)).unwrap(), &game);
+
+        game.undo()?;
+        let mut loaded = from_value(to_value(&game)).unwrap();
+        assert_same_game(&loaded, &game);
+        loaded.redo()?;
+        game.redo()?;
+        assert_same_game(&loaded, &game);
+    }
+    Ok(())
+}
+
+#[test]
+fn small_types() {
+    let team: Team = serde_json::from_str(&serde_json::to_string(&Team::new(3)).unwrap()).unwrap();
+    assert_eq!(team, Team::new(3));
+    let error: Error =
+        serde_json::from_str(&serde_json::to_string(&Error::ColumnFull).unwrap()).unwrap();
+    assert_eq!(error, Error::ColumnFull);
+    let config = GameConfig::default();
+    let loaded: GameConfig = serde_json::from_value(serde_json::to_value(config).unwrap()).unwrap();
+    assert_eq!(loaded, config);
+}
+
+#[test]
+fn rejects_floating_chips() -> Result<(), Error> {
+    let mut value = to_value(&GameState::default());
+    value["cells"][2][0] = json!(0);
+    assert!(from_value(value).is_err());
+    Ok(())
+}
+
+#[test]
+fn rejects_wrong_turn() -> Result<(), Error> {
+    let mut value = to_value(&sample_game()?);
+    value["cur_turn"] = json!(2);
+    assert!(from_value(value).is_err());
+    Ok(())
+}
+
+#[test]
+fn rejects_starting_chips_out_of_turn() -> Result<(), MoveStringError> {
+    let mut value = to_value(&GameState::default());
+    for row in 0..4 {
+        value["cells"][row][0] = json!(0);
+    }
+    assert!(from_value(value).is_err());
+
+    // Team 0 playing twice in a row is just as wrong with history on top
+    let mut value = to_value(&GameState::from_moves("4", &GameConfig::default())?);
+    value["cells"][0][0] = json!(0);
+    assert!(from_value(value).is_err());
+
+    // One chip from team 0 means team 1 plays next
+    let mut value = to_value(&GameState::default());
+    value["cells"][0][0] = json!(0);
+    assert!(from_value(value.clone()).is_err());
+    value["first_turn"] = json!(1);
+    value["cur_turn"] = json!(1);
+    assert!(from_value(value.clone()).is_ok());
+    value["cells"][0][1] = json!(1);
+    assert!(from_value(value.clone()).is_ok());
+    value["cells"][1][1] = json!(1);
+    assert!(from_value(value).is_err());
+    Ok(())
+}
+
+#[test]
+fn rejects_history_that_does_not_match_the_board() -> Result<(), Error> {
+    let game = sample_game()?;
+    let mut value = to_value(&game);
+    value["history"][0]["column"] = json!(6);
+    assert!(from_value(value).is_err());
+
+    let mut value = to_value(&game);
+    value["undone"][0]["row"] = json!(5);
+    assert!(from_value(value).is_err());
+    Ok(())
+}
+
+#[test]
+fn rejects_incompatible_rules() -> Result<(), Error> {
+    let mut value = to_value(&GameState::default());
+    value["pop_out"] = json!(true);
+    value["scoring"] = json!("CountLines");
+    assert!(from_value(value).is_err());
+    Ok(())
+}
+
+#[test]
+fn rejects_invalid_teams() -> Result<(), Error> {
+    let mut value = to_value(&sample_game()?);
+    value["cells"][0][0] = json!(7);
+    assert!(from_value(value).is_err());
+
+    let mut value = to_value(&sample_game()?);
+    value["team_info"][0]["glyph"] = json!("B");
+    assert!(from_value(value).is_err());
+
+    let mut value = to_value(&sample_game()?);
+    value["winning_length"] = json!(9);
+    assert!(from_value(value).is_err());
+    Ok(())
+}
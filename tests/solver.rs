@@ -0,0 +1,149 @@
+extern crate connect_four;
+
+use connect_four::bitboard::*;
+use connect_four::game::*;
+use connect_four::solver::*;
+
+/// A plain alpha-beta search over every move, returning the number of
+/// chips until the end of the game along with who wins.
+fn brute_force(board: &BitBoard, mut alpha: i32, beta: i32) -> i32 {
+    let num_cells = (board.num_rows() * board.num_columns()) as i32;
+    if board.game_over() {
+        // The previous team just won, or the board is full
+        return match board.outcome() {
+            GameOutcome::Won(_) => -(num_cells + 2 - board.num_moves() as i32) / 2,
+            _ => 0,
+        };
+    }
+    let mut best = i32::MIN + 1;
+    for column in 0..board.num_columns() {
+        if !board.can_play(column) {
+            continue;
+        }
+        let mut child = *board;
+        child.play(column);
+        let value = -brute_force(&child, -beta, -alpha);
+        best = best.max(value);
+        alpha = alpha.max(value);
+        if alpha >= beta {
+            break;
+        }
+    }
+    best
+}
+
+fn random_position(seed: u64, num_moves: usize) -> Option<GameState> {
+    let mut game = GameState::default();
+    let mut state = seed;
+    while game.history().len() < num_moves {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let column = (state >> 33) as usize % game.num_columns();
+        let team = game.cur_turn();
+        let _ = game.drop_chip(team, column);
+        if game.game_over() {
+            return None;
+        }
+    }
+    Some(game)
+}
+
+fn expected_score(game: &GameState) -> Score {
+    let board = BitBoard::from_game_state(game).unwrap();
+    let value = brute_force(&board, -100, 100);
+    let num_moves = board.num_moves() as i32;
+    let num_cells = 42;
+    if value == 0 {
+        return Score::Draw;
+    }
+    // Undo the scaling used in `brute_force` to get the distance
+    let last_move = if value > 0 {
+        num_cells + 1 - 2 * value
+    } else {
+        num_cells + 2 - 2 * -value
+    };
+    let candidates = [last_move, last_move + 1];
+    let wanted_parity = if value > 0 { num_moves + 1 } else { num_moves } % 2;
+    let last_move = *candidates
+        .iter()
+        .find(|&&candidate| candidate % 2 == wanted_parity)
+        .unwrap();
+    let distance = (last_move - num_moves) as usize;
+    if value > 0 {
+        Score::Win(distance)
+    } else {
+        Score::Loss(distance)
+    }
+}
+
+#[test]
+fn matches_brute_force() {
+    let mut solver = Solver::new();
+    let mut checked = 0;
+    for seed in 0..100 {
+        if let Some(game) = random_position(seed, 28) {
+            assert_eq!(
+                solver.solve(&game),
+                Ok(expected_score(&game)),
+                "seed {}",
+                seed
+            );
+            checked += 1;
+        }
+    }
+    assert!(checked > 10);
+}
+
+#[test]
+fn immediate_win_and_loss() -> Result<(), MoveStringError> {
+    let config = GameConfig::default();
+    // Team 0 has three in column 1 and it is their turn
+    let game = GameState::from_moves("121212", &config)?;
+    assert_eq!(solve(&game), Ok(Score::Win(1)));
+    // Team 1 can only block one of two threats
+    let game = GameState::from_moves("44553", &config)?;
+    assert_eq!(solve(&game), Ok(Score::Loss(2)));
+    Ok(())
+}
+
+#[test]
+fn finished_games() -> Result<(), MoveStringError> {
+    let config = GameConfig::default();
+    let game = GameState::from_moves("1212121", &config)?;
+    assert_eq!(solve(&game), Ok(Score::Loss(0)));
+    assert_eq!(best_moves(&game), Ok(vec![]));
+    Ok(())
+}
+
+fn rank(score: Score) -> i32 {
+    match score {
+        Score::Win(moves) => 100 - moves as i32,
+        Score::Draw => 0,
+        Score::Loss(moves) => moves as i32 - 100,
+    }
+}
+
+#[test]
+fn analyze_every_column() -> Result<(), MoveStringError> {
+    let game = GameState::from_moves("444444555555", &GameConfig::default())?;
+    let mut solver = Solver::new();
+    let scores = solver.analyze(&game).unwrap();
+    assert_eq!(scores.len(), DEFAULT_NUM_COLUMNS);
+    assert_eq!(scores[3], None);
+    assert_eq!(scores[4], None);
+    let best_rank = scores.iter().flatten().map(|&score| rank(score)).max();
+    for column in solver.best_moves(&game).unwrap() {
+        assert_eq!(scores[column].map(rank), best_rank);
+    }
+    Ok(())
+}
+
+#[test]
+fn rejects_other_boards() -> Result<(), Error> {
+    let game = GameState::new(Team::new(0), 2, 6, 7, 5)?;
+    assert_eq!(solve(&game), Err(Error::UnsupportedBoard));
+    let game = GameState::new(Team::new(0), 3, 6, 7, 4)?;
+    assert_eq!(solve(&game), Err(Error::UnsupportedBoard));
+    Ok(())
+}